@@ -0,0 +1,23 @@
+//! Prints every braille character in a 16 by 16 grid, ordered by their data.
+//! Rows go up by 16, columns by 1.
+
+use braille_rs::{braille_char, Canvas};
+
+
+fn main() {
+    let mut canvas = Canvas::for_terminal(16, 16);
+
+    for (i, c) in braille_char::all().enumerate() {
+        let (cx, cy) = (i % 16, i / 16);
+
+        for x in 0..2 {
+            for y in 0..4 {
+                if c.get(x, y).unwrap() {
+                    canvas.set(cx * 2 + x, cy * 4 + y).unwrap();
+                }
+            }
+        }
+    }
+
+    println!("{canvas}");
+}
\ No newline at end of file
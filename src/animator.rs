@@ -0,0 +1,167 @@
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::canvas::Canvas;
+
+
+/// Returns `s` as a quoted JSON string.
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+
+/// Turns rendered frames, for example collected with `Canvas::append_frame()`,
+/// into an asciicast v2 recording that can be played back with asciinema.
+/// Frames are shown one after another at `fps` frames per second, each one clearing the screen.
+/// The terminal size of the recording fits the largest frame.
+/// An `fps` of 0 is treated as 1.
+pub fn frames_to_asciicast(frames: &[String], fps: u32) -> String {
+    let width = frames
+        .iter()
+        .flat_map(|frame| frame.lines())
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let height = frames.iter().map(|frame| frame.lines().count()).max().unwrap_or(0);
+
+    let mut cast = format!("{{\"version\": 2, \"width\": {width}, \"height\": {height}}}\n");
+
+    for (i, frame) in frames.iter().enumerate() {
+        let time = i as f64 / fps.max(1) as f64;
+        let output = format!("\x1b[H\x1b[2J{}", frame.replace('\n', "\r\n"));
+
+        cast.push_str(&format!("[{time:.3}, \"o\", {}]\n", json_string(&output)));
+    }
+
+    cast
+}
+
+
+/// Draws an animation frame by frame onto a canvas and prints it to the terminal.
+/// Every frame the canvas gets reset and `frame` is called with it and the seconds elapsed
+/// since the animation started. Once `frame` returns `false` the animation stops
+/// after printing that last frame.
+pub struct Animator<F: FnMut(&mut Canvas, f64) -> bool> {
+    canvas: Canvas,
+    frame: F,
+}
+
+
+impl<F: FnMut(&mut Canvas, f64) -> bool> Animator<F> {
+    /// Creates a new Animator drawing onto `canvas` with the callback `frame`.
+    pub fn new(canvas: Canvas, frame: F) -> Self {
+        Animator {
+            canvas,
+            frame,
+        }
+    }
+
+    /// Returns the canvas, holding the last frame that was drawn.
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    /// Consumes the Animator and returns the canvas.
+    pub fn into_canvas(self) -> Canvas {
+        self.canvas
+    }
+
+    /// Runs the animation on stdout at `fps` frames per second, see `run_with()`.
+    pub fn run(&mut self, fps: u32) -> io::Result<()> {
+        self.run_with(&mut io::stdout(), fps)
+    }
+
+    /// Runs the animation at `fps` frames per second, writing every frame to `writer`.
+    /// The first frame is drawn completely, later frames only write the characters that changed,
+    /// positioned with ANSI escape codes like `Canvas::write_diff()`.
+    /// Frames that take too long to draw aren't skipped, the animation just runs late.
+    /// An `fps` of 0 draws frames as fast as possible.
+    pub fn run_with<W: io::Write>(&mut self, writer: &mut W, fps: u32) -> io::Result<()> {
+        let interval = if fps == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs(1) / fps
+        };
+        let start = Instant::now();
+        let mut previous = Canvas::new();
+        let mut next_frame = start;
+
+        loop {
+            self.canvas.reset_all();
+            let running = (self.frame)(&mut self.canvas, start.elapsed().as_secs_f64());
+
+            self.canvas.write_diff(&previous, writer)?;
+
+            if !running {
+                return Ok(());
+            }
+
+            previous.clone_from(&self.canvas);
+            next_frame += interval;
+            thread::sleep(next_frame.saturating_duration_since(Instant::now()));
+        }
+    }
+}
+
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn animator_run_with() {
+        let mut frames = 0;
+        let mut animator = Animator::new(Canvas::with_size(4, 4), |canvas, elapsed| {
+            assert!(elapsed >= 0.0);
+            canvas.set(frames, 0).unwrap();
+            frames += 1;
+
+            frames < 3
+        });
+
+        let mut output = Vec::new();
+        animator.run_with(&mut output, 0).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\x1b[1;1H⠁\x1b[1;2H⠀\x1b[1;1H⠈\x1b[1;1H⠀\x1b[1;2H⠁"
+        );
+        assert_eq!(animator.canvas().to_string(), "⠀⠁");
+    }
+
+    #[test]
+    fn animator_frames_to_asciicast() {
+        let mut canvas = Canvas::with_size(4, 8);
+        let mut frames = Vec::new();
+        canvas.append_frame(&mut frames);
+        canvas.set(0, 0).unwrap();
+        canvas.append_frame(&mut frames);
+
+        assert_eq!(
+            frames_to_asciicast(&frames, 2),
+            concat!(
+                "{\"version\": 2, \"width\": 2, \"height\": 2}\n",
+                "[0.000, \"o\", \"\\u001b[H\\u001b[2J⠀⠀\\r\\n⠀⠀\"]\n",
+                "[0.500, \"o\", \"\\u001b[H\\u001b[2J⠁⠀\\r\\n⠀⠀\"]\n",
+            )
+        );
+        assert_eq!(frames_to_asciicast(&[], 0), "{\"version\": 2, \"width\": 0, \"height\": 0}\n");
+        assert_eq!(json_string("\"a\\b\""), "\"\\\"a\\\\b\\\"\"");
+    }
+}
\ No newline at end of file
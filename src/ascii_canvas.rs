@@ -0,0 +1,148 @@
+use std::fmt::Display;
+
+use crate::{
+    canvas::Canvas,
+    error::IndexError,
+    terminal::terminal_supports_braille,
+};
+
+
+/// Decides how an `AsciiCanvas` gets rendered.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum OutputMode {
+    /// Render as braille characters like `Canvas` does.
+    #[default]
+    Braille,
+    /// Render every point as `#` if set and `.` if not, one line per row of points.
+    Ascii,
+}
+
+
+impl OutputMode {
+    /// Returns `Braille` if `terminal_supports_braille()` thinks the terminal can show it
+    /// and `Ascii` otherwise.
+    pub fn detect() -> Self {
+        if terminal_supports_braille() {
+            OutputMode::Braille
+        } else {
+            OutputMode::Ascii
+        }
+    }
+}
+
+
+/// Wraps a `Canvas` so it can be rendered either as braille or as plain ASCII,
+/// for screen readers and terminals that can't handle braille.
+/// The output mode can be switched at any time without drawing again.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AsciiCanvas {
+    canvas: Canvas,
+    mode: OutputMode,
+}
+
+
+impl AsciiCanvas {
+    /// Creates a new AsciiCanvas with size `x` by `y` that renders as braille.
+    pub fn with_size(x: usize, y: usize) -> Self {
+        AsciiCanvas::from_canvas(Canvas::with_size(x, y), OutputMode::default())
+    }
+
+    /// Wraps an existing canvas.
+    pub fn from_canvas(canvas: Canvas, mode: OutputMode) -> Self {
+        AsciiCanvas {
+            canvas,
+            mode,
+        }
+    }
+
+    /// Returns the current output mode.
+    pub fn mode(&self) -> OutputMode {
+        self.mode
+    }
+
+    /// Changes how the canvas gets rendered.
+    pub fn set_mode(&mut self, mode: OutputMode) {
+        self.mode = mode;
+    }
+
+    /// Returns the underlying canvas.
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    /// Returns the underlying canvas mutably, for drawing with the full `Canvas` API.
+    pub fn canvas_mut(&mut self) -> &mut Canvas {
+        &mut self.canvas
+    }
+
+    /// Unwraps the underlying canvas.
+    pub fn into_canvas(self) -> Canvas {
+        self.canvas
+    }
+
+    /// Returns whether the point at a given x, y position is set. See `Canvas::get()`.
+    pub fn get(&self, x: usize, y: usize) -> Result<bool, IndexError> {
+        self.canvas.get(x, y)
+    }
+
+    /// Sets the point at a given x, y position. See `Canvas::set()`.
+    pub fn set(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
+        self.canvas.set(x, y)
+    }
+
+    /// Resets the point at a given x, y position. See `Canvas::reset()`.
+    pub fn reset(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
+        self.canvas.reset(x, y)
+    }
+
+    /// Flips the point at a given x, y position. See `Canvas::flip()`.
+    pub fn flip(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
+        self.canvas.flip(x, y)
+    }
+
+    /// Renders the canvas as `#` and `.` regardless of the output mode.
+    pub fn to_ascii_string(&self) -> String {
+        let mut string = String::new();
+
+        for (y, row) in (0..).map_while(|y| self.canvas.row_bits(y).ok()).enumerate() {
+            if y != 0 {
+                string.push('\n');
+            }
+
+            string.extend(row.iter().map(|&set| if set { '#' } else { '.' }));
+        }
+
+        string
+    }
+}
+
+
+impl Display for AsciiCanvas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.mode {
+            OutputMode::Braille => write!(f, "{}", self.canvas),
+            OutputMode::Ascii => write!(f, "{}", self.to_ascii_string()),
+        }
+    }
+}
+
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_canvas_modes() {
+        let mut c = AsciiCanvas::with_size(3, 2);
+        c.set(0, 0).unwrap();
+        c.flip(2, 1).unwrap();
+        assert_eq!(c.to_string(), "⠁⠂");
+
+        c.set_mode(OutputMode::Ascii);
+        assert_eq!(c.to_string(), "#..\n..#");
+
+        c.reset(0, 0).unwrap();
+        assert_eq!(c.to_string(), "...\n..#");
+        assert!(c.set(3, 0).is_err());
+    }
+}
\ No newline at end of file
@@ -1,5 +1,5 @@
+use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
-use std::ops::Not;
 
 use crate::error::IndexError;
 
@@ -16,20 +16,99 @@ use crate::error::IndexError;
 ///
 pub(crate) const BYTE_MASK: [u32; 8] = [1, 2, 4, 64, 8, 16, 32, 128];
 pub(crate) const BYTE_MASK_INVERTED: [u32; 8] = [!1, !2, !4, !64, !8, !16, !32, !128];
+/// Order in which `BrailleChar::from_brightness()` lights up dots, filling from the bottom up.
+const BRIGHTNESS_ORDER: [u32; 8] = [64, 128, 4, 32, 2, 16, 1, 8];
+
+
+/// Returns the braille character the lowest 8 bit of `data` represent.
+pub(crate) fn data_to_char(data: u32) -> char {
+    char::from_u32(0x00002800 | (data & 0b11111111u32)).unwrap()
+}
+
+
+/// Returns the braille character for the dots set in `bits`,
+/// without going through `BrailleChar`.
+///
+/// LSB is top left and MSB is bottom right. However, it goes up in the following order,
+/// where 0 is the LSB and 7 the MSB:
+///
+/// `(0) (3)`
+///
+/// `(1) (4)`
+///
+/// `(2) (5)`
+///
+/// `(6) (7)`
+///
+/// Meaning `0b00111010` would result in ⠺
+///
+pub fn from_bits(bits: u8) -> char {
+    data_to_char(bits as u32)
+}
+
+
+/// Returns the dots set in the braille character `c`, in the same order as `from_bits()`.
+/// Returns `None` if `c` is not a braille character.
+pub fn into_bits(c: char) -> Option<u8> {
+    let value = c as u32;
+
+    if (0x2800..=0x28ff).contains(&value) {
+        Some((value & 0b11111111) as u8)
+    } else {
+        None
+    }
+}
+
+
+/// Returns an iterator over all 256 braille characters in order of their data,
+/// starting with the blank character.
+pub fn all() -> impl Iterator<Item = BrailleChar> {
+    (0..=255u8).map(BrailleChar::with_data)
+}
+
+
+/// Returns the braille character closest to the 2*4 bitmap `target`.
+/// Since there is a character for every bitmap this is always an exact match.
+/// `target` uses the same bit order as `BrailleChar::with_data()`.
+pub fn nearest(target: u8) -> BrailleChar {
+    BrailleChar::with_data(target)
+}
+
+
+/// Returns the 6 dot braille character closest to the 2*4 bitmap `target`,
+/// measured by the amount of differing dots like `BrailleChar::distance()`.
+/// If several characters are equally close the one with the lowest data wins.
+/// In practice the closest character is always `target` with its bottom row cleared.
+/// `target` uses the same bit order as `BrailleChar::with_data()`.
+pub fn nearest_six_dot(target: u8) -> BrailleChar {
+    let target = BrailleChar::with_data(target);
+
+    all()
+        .filter(BrailleChar::is_six_dot)
+        .min_by_key(|c| c.distance(&target))
+        .unwrap()
+}
 
 
 /// Represents a braille character.
-#[derive(Debug, Copy, Clone, PartialEq)]
+///
+/// Characters are ordered by density, meaning the amount of set dots, not by their numeric value.
+/// Characters with the same amount of dots are ordered by their dots' data.
+/// The reserved upper bits and then the blink flag only break ties between otherwise equal characters.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct BrailleChar {
     data: u32,
+    blink: bool,
 }
 
 
 impl BrailleChar {
     /// Creates a new braille character that is blank.
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         BrailleChar {
             data: 0,
+            blink: false,
         }
     }
 
@@ -46,9 +125,24 @@ impl BrailleChar {
     ///
     /// `(6) (7)`
     ///
-    pub fn with_data(data: u8) -> Self {
+    pub const fn with_data(data: u8) -> Self {
         BrailleChar {
             data: data as u32,
+            blink: false,
+        }
+    }
+
+    /// Creates a new braille character with an amount of dots matching the brightness `level`,
+    /// where 0.0 is blank and 1.0 has all 8 dots set. Out of range levels are clamped.
+    ///
+    /// Dots get filled row by row from the bottom up, left before right,
+    /// so 0.5 sets the bottom two rows: ⣤
+    pub fn from_brightness(level: f32) -> Self {
+        let count = (level.clamp(0.0, 1.0) * 8.0).round() as usize;
+
+        BrailleChar {
+            data: BRIGHTNESS_ORDER[..count].iter().fold(0, |data, mask| data | mask),
+            blink: false,
         }
     }
 
@@ -83,22 +177,34 @@ impl BrailleChar {
         self.data ^= data;
     }
 
-    /// Takes an u32 and performs logical and on the data
+    /// Takes an u32 and performs logical and on the data.
     fn and_data(&mut self, data: u32) {
-        self.data &= data.not();
+        self.data &= data;
+    }
+
+    /// Returns whether the point at a given x, y position is set. 0, 0 is top left.
+    /// If x or y are out of range returns an `IndexError`.
+    pub fn get(&self, x: usize, y: usize) -> Result<bool, IndexError> {
+        if x < 2 && y < 4 {
+            let i = y + 4 * x;
+
+            Ok(self.data & BYTE_MASK[i] != 0)
+        } else {
+            Err(IndexError::USizeMatrix(2, 4, x, y))
+        }
     }
 
     /// Sets the point at a given x, y position. 0, 0 is top left.
     /// If x or y are out of range returns an `IndexError`.
     pub fn set(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
-        let i = y + 4 * x;
+        if x < 2 && y < 4 {
+            let i = y + 4 * x;
 
-        return if i < 8 {
-            self.or_data(BYTE_MASK[y + 4 * x]);
+            self.or_data(BYTE_MASK[i]);
             Ok(())
         } else {
             Err(IndexError::USizeMatrix(2, 4, x, y))
-        };
+        }
     }
 
     /// Sets all points.
@@ -109,14 +215,14 @@ impl BrailleChar {
     /// Resets the point at a given x, y position. 0, 0 is top left.
     /// If x or y are out of range returns an `IndexError`.
     pub fn reset(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
-        let i = y + 4 * x;
+        if x < 2 && y < 4 {
+            let i = y + 4 * x;
 
-        return if i < 8 {
-            self.and_data(BYTE_MASK_INVERTED[y + 4 * x]);
+            self.and_data(BYTE_MASK_INVERTED[i]);
             Ok(())
         } else {
             Err(IndexError::USizeMatrix(2, 4, x, y))
-        };
+        }
     }
 
     /// Resets all points.
@@ -127,20 +233,83 @@ impl BrailleChar {
     /// Clears the point at a given x, y position. 0, 0 is top left.
     /// If x or y are out of range returns an `IndexError`.
     pub fn flip(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
-        let i = y + 4 * x;
+        if x < 2 && y < 4 {
+            let i = y + 4 * x;
 
-        return if i < 8 {
-            self.xor_data(BYTE_MASK[y + 4 * x]);
+            self.xor_data(BYTE_MASK[i]);
             Ok(())
         } else {
             Err(IndexError::USizeMatrix(2, 4, x, y))
-        };
+        }
     }
 
     /// Flips all points.
     pub fn flip_all(&mut self) {
         self.data ^= 0b11111111;
     }
+
+    /// Returns the amount of dots that differ between both characters.
+    /// The reserved upper bits are ignored.
+    pub fn distance(&self, other: &BrailleChar) -> u32 {
+        ((self.data ^ other.data) & 0b11111111).count_ones()
+    }
+
+    /// Returns a character with every dot that is set in either character.
+    /// The reserved upper bits of the result are empty.
+    pub fn overlay(&self, other: &BrailleChar) -> BrailleChar {
+        BrailleChar {
+            data: (self.data | other.data) & 0b11111111,
+            blink: false,
+        }
+    }
+
+    /// Returns a character with every dot of this character that isn't set in `other`.
+    /// The reserved upper bits of the result are empty.
+    pub fn subtract(&self, other: &BrailleChar) -> BrailleChar {
+        BrailleChar {
+            data: self.data & !other.data & 0b11111111,
+            blink: false,
+        }
+    }
+
+    /// Flags the character to blink or clears the flag.
+    /// The flag is kept apart from the data, so neither the dots nor the reserved bits change.
+    /// Only renderers that support it, like `Canvas::to_ansi_string_with_blink()`, make use of it.
+    pub fn set_blink(&mut self, on: bool) {
+        self.blink = on;
+    }
+
+    /// Returns whether the character is flagged to blink, see `set_blink()`.
+    pub fn is_blink(&self) -> bool {
+        self.blink
+    }
+
+    /// Returns whether the character only uses the 6 dots of traditional braille,
+    /// meaning the bottom row (bit 6 and 7) is empty.
+    pub fn is_six_dot(&self) -> bool {
+        self.data & 0b11000000 == 0
+    }
+}
+
+
+impl Ord for BrailleChar {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let dots = |c: &BrailleChar| c.data & 0b11111111;
+
+        dots(self)
+            .count_ones()
+            .cmp(&dots(other).count_ones())
+            .then(dots(self).cmp(&dots(other)))
+            .then(self.data.cmp(&other.data))
+            .then(self.blink.cmp(&other.blink))
+    }
+}
+
+
+impl PartialOrd for BrailleChar {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 
@@ -160,7 +329,7 @@ impl From<BrailleChar> for char {
 
 impl From<&BrailleChar> for char {
     fn from(value: &BrailleChar) -> Self {
-        char::from_u32(0x00002800 | (value.data & 0b11111111u32)).unwrap()
+        data_to_char(value.data)
     }
 }
 
@@ -188,6 +357,43 @@ pub mod tests {
         assert_eq!(c, BrailleChar::new());
     }
 
+    #[test]
+    fn braille_character_get() {
+        let c = BrailleChar::with_data(0b10000100);
+
+        assert!(c.get(0, 2).unwrap());
+        assert!(c.get(1, 3).unwrap());
+        assert!(!c.get(0, 0).unwrap());
+        assert!(c.get(2, 0).is_err());
+    }
+
+    #[test]
+    fn braille_character_bounds() {
+        let mut c = BrailleChar::new();
+
+        assert!(c.set(0, 5).is_err());
+        assert!(c.set(1, 4).is_err());
+        assert!(c.set(2, 0).is_err());
+        assert!(c.reset(0, 7).is_err());
+        assert!(c.flip(0, 6).is_err());
+        assert!(c.get(0, 4).is_err());
+        assert_eq!(c, BrailleChar::new());
+
+        c.set(1, 3).unwrap();
+        assert_eq!(c, BrailleChar::with_data(0b10000000));
+    }
+
+    #[test]
+    fn braille_character_reset_single() {
+        let mut c = BrailleChar::with_data(0b11111111);
+
+        c.reset(0, 1).unwrap();
+        assert_eq!(c, BrailleChar::with_data(0b11111101));
+
+        c.reset(1, 3).unwrap();
+        assert_eq!(c, BrailleChar::with_data(0b01111101));
+    }
+
     #[test]
     fn braille_character_all() {
         let mut c = BrailleChar::new();
@@ -201,4 +407,118 @@ pub mod tests {
         c.flip_all();
         assert_eq!(c, BrailleChar::with_data(255));
     }
+
+    #[test]
+    fn braille_character_iter_all() {
+        let chars: Vec<BrailleChar> = all().collect();
+
+        assert_eq!(chars.len(), 256);
+        assert_eq!(chars[0], BrailleChar::new());
+        assert_eq!(chars[0b00111010], BrailleChar::with_data(0b00111010));
+        assert_eq!(char::from(chars[255]), '⣿');
+    }
+
+    #[test]
+    fn braille_character_is_six_dot() {
+        assert!(BrailleChar::new().is_six_dot());
+        assert!(BrailleChar::with_data(0b00111111).is_six_dot());
+        assert!(!BrailleChar::with_data(0b01000000).is_six_dot());
+        assert!(!BrailleChar::with_data(0b10000001).is_six_dot());
+    }
+
+    #[test]
+    fn braille_character_blink() {
+        let mut c = BrailleChar::with_data(0b00111010);
+        assert!(!c.is_blink());
+
+        c.set_blink(true);
+        assert!(c.is_blink());
+        assert_eq!(c.to_string(), "⠺");
+        c.set(0, 0).unwrap();
+        assert!(c.is_blink());
+
+        c.set_data(0xff << 8 | 0b00111011);
+        assert!(c.is_blink());
+
+        c.set_blink(false);
+        assert!(!c.is_blink());
+        c.set_data(0b00111011);
+        assert_eq!(c, BrailleChar::with_data(0b00111011));
+    }
+
+    #[test]
+    fn braille_character_from_brightness() {
+        assert_eq!(BrailleChar::from_brightness(0.0), BrailleChar::new());
+        assert_eq!(BrailleChar::from_brightness(0.5), BrailleChar::with_data(0b11100100));
+        assert_eq!(BrailleChar::from_brightness(1.0), BrailleChar::with_data(255));
+        assert_eq!(BrailleChar::from_brightness(-3.0), BrailleChar::new());
+        assert_eq!(BrailleChar::from_brightness(7.0), BrailleChar::with_data(255));
+        assert_eq!(BrailleChar::from_brightness(0.125), BrailleChar::with_data(0b01000000));
+    }
+
+    #[test]
+    fn braille_character_ordering() {
+        let mut chars = vec![
+            BrailleChar::with_data(0b11111111),
+            BrailleChar::with_data(0b10000000),
+            BrailleChar::with_data(0b00000011),
+            BrailleChar::with_data(0b00000001),
+            BrailleChar::new(),
+        ];
+        chars.sort();
+
+        assert_eq!(chars, vec![
+            BrailleChar::new(),
+            BrailleChar::with_data(0b00000001),
+            BrailleChar::with_data(0b10000000),
+            BrailleChar::with_data(0b00000011),
+            BrailleChar::with_data(0b11111111),
+        ]);
+
+        let mut colored = BrailleChar::with_data(0b00000001);
+        colored.set_data(0xff000001);
+        assert!(colored < BrailleChar::with_data(0b00000010));
+    }
+
+    #[test]
+    fn braille_character_distance() {
+        let a = BrailleChar::with_data(0b10101010);
+        let b = BrailleChar::with_data(0b10100101);
+        assert_eq!(a.distance(&b), 4);
+        assert_eq!(a.distance(&a), 0);
+
+        let mut colored = a;
+        colored.set_data(0xffffff00 | 0b10101010);
+        assert_eq!(a.distance(&colored), 0);
+    }
+
+    #[test]
+    fn braille_character_overlay_subtract() {
+        let a = BrailleChar::with_data(0b00000111);
+        let mut b = BrailleChar::with_data(0b00111100);
+        assert_eq!(a.overlay(&b), BrailleChar::with_data(0b00111111));
+        assert_eq!(a.subtract(&b), BrailleChar::with_data(0b00000011));
+        assert_eq!(b.subtract(&a), BrailleChar::with_data(0b00111000));
+
+        b.set_data(0xff000000 | 0b00111100);
+        assert_eq!(a.overlay(&b), BrailleChar::with_data(0b00111111));
+        assert_eq!(b.subtract(&a), BrailleChar::with_data(0b00111000));
+    }
+
+    #[test]
+    fn braille_character_nearest() {
+        assert_eq!(nearest(0b10100101), BrailleChar::with_data(0b10100101));
+        assert_eq!(nearest_six_dot(0b10100101), BrailleChar::with_data(0b00100101));
+        assert_eq!(nearest_six_dot(0b11000000), BrailleChar::new());
+        assert_eq!(nearest_six_dot(0b00111111), BrailleChar::with_data(0b00111111));
+    }
+
+    #[test]
+    fn braille_character_bits() {
+        assert_eq!(from_bits(0b00111010), '⠺');
+        assert_eq!(from_bits(0), '⠀');
+        assert_eq!(into_bits('⠺'), Some(0b00111010));
+        assert_eq!(into_bits('a'), None);
+        assert!((0..=255u8).all(|bits| into_bits(from_bits(bits)) == Some(bits)));
+    }
 }
\ No newline at end of file
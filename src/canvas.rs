@@ -1,12 +1,276 @@
 use std::cmp::min;
+use std::collections::BTreeSet;
 use std::fmt::Display;
+use std::io;
 
 use crate::{
+    canvas_view::CanvasView,
     error::IndexError,
-    braille_char::{BYTE_MASK, BYTE_MASK_INVERTED}
+    icons::Icon,
+    plot,
+    braille_char::{BrailleChar, BYTE_MASK, data_to_char}
 };
 
 
+/// Magic bytes every serialized canvas starts with.
+const BYTES_MAGIC: [u8; 4] = *b"BRLC";
+/// Current version of the serialized canvas format.
+const BYTES_VERSION: u8 = 1;
+/// Length of the header of the serialized format: magic, version, width, height, flags and dot table.
+const BYTES_HEADER_LEN: usize = 4 + 1 + 8 + 8 + 1 + 8;
+/// Flag marking a canvas with `Origin::BottomLeft`.
+const BYTES_FLAG_BOTTOM_LEFT: u8 = 1;
+/// Flag marking a canvas in strict mode.
+const BYTES_FLAG_STRICT: u8 = 2;
+
+
+/// Returns every point on the line from `x0`, `y0` to `x1`, `y1` using Bresenham's algorithm.
+/// Both endpoints are included.
+fn line_points(x0: isize, y0: isize, x1: isize, y1: isize) -> Vec<(isize, isize)> {
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+
+    let mut points = Vec::with_capacity(dx.max(-dy) as usize + 1);
+    let (mut x, mut y) = (x0, y0);
+    let mut err = dx + dy;
+
+    loop {
+        points.push((x, y));
+
+        if x == x1 && y == y1 {
+            break;
+        }
+
+        let e2 = 2 * err;
+
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+
+    points
+}
+
+
+/// Clips the line from `x0`, `y0` to `x1`, `y1` to the rectangle from 0, 0 to `max_x`, `max_y`
+/// using the Liang-Barsky algorithm. Endpoints inside of the rectangle are returned unchanged.
+/// Returns `None` if the line misses the rectangle.
+fn clip_line(
+    (x0, y0): (isize, isize),
+    (x1, y1): (isize, isize),
+    max_x: isize,
+    max_y: isize,
+) -> Option<((isize, isize), (isize, isize))> {
+    let (dx, dy) = (x1 as f64 - x0 as f64, y1 as f64 - y0 as f64);
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    let edges = [
+        (-dx, x0 as f64),
+        (dx, max_x as f64 - x0 as f64),
+        (-dy, y0 as f64),
+        (dy, max_y as f64 - y0 as f64),
+    ];
+
+    for (p, q) in edges {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+
+    if t0 > t1 {
+        return None;
+    }
+
+    let at = |t: f64| {
+        (
+            (x0 as f64 + t * dx).round().clamp(0.0, max_x as f64) as isize,
+            (y0 as f64 + t * dy).round().clamp(0.0, max_y as f64) as isize,
+        )
+    };
+    let start = if t0 == 0.0 { (x0, y0) } else { at(t0) };
+    let end = if t1 == 1.0 { (x1, y1) } else { at(t1) };
+
+    Some((start, end))
+}
+
+
+/// Returns the corners of the convex hull of `points` in order using Andrew's monotone chain.
+/// Duplicate points and points on an edge of the hull are dropped.
+fn hull_points(points: &[(isize, isize)]) -> Vec<(isize, isize)> {
+    let mut points = points.to_vec();
+    points.sort_unstable();
+    points.dedup();
+
+    if points.len() < 3 {
+        return points;
+    }
+
+    let cross = |o: (isize, isize), a: (isize, isize), b: (isize, isize)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+    let mut hull: Vec<(isize, isize)> = Vec::with_capacity(points.len() * 2);
+
+    // Lower half from left to right, then upper half back from right to left.
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+
+        for p in pass {
+            while hull.len() >= start + 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0 {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+
+        // The last point is the first point of the other half.
+        hull.pop();
+    }
+
+    hull
+}
+
+
+/// Splits the cubic Bézier curve given by `p` in half until every piece is flat enough to be
+/// drawn as a line, pushing the end point of every piece to `points`.
+fn subdivide_cubic(p: [(f64, f64); 4], depth: usize, points: &mut Vec<(f64, f64)>) {
+    // Distance of the control points from the line between the end points.
+    let (dx, dy) = (p[3].0 - p[0].0, p[3].1 - p[0].1);
+    let length = (dx * dx + dy * dy).sqrt().max(f64::EPSILON);
+    let distance = |q: (f64, f64)| ((q.0 - p[0].0) * dy - (q.1 - p[0].1) * dx).abs() / length;
+
+    if depth == 0 || distance(p[1]).max(distance(p[2])) < 0.25 {
+        points.push(p[3]);
+        return;
+    }
+
+    let mid = |a: (f64, f64), b: (f64, f64)| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    let (ab, bc, cd) = (mid(p[0], p[1]), mid(p[1], p[2]), mid(p[2], p[3]));
+    let (abc, bcd) = (mid(ab, bc), mid(bc, cd));
+    let center = mid(abc, bcd);
+
+    subdivide_cubic([p[0], ab, abc, center], depth - 1, points);
+    subdivide_cubic([center, bcd, cd, p[3]], depth - 1, points);
+}
+
+
+/// Advances the splitmix64 state and returns the next pseudo random number.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+
+/// Decides which dots are kept when reading braille characters into a canvas.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum DotMode {
+    /// Keep all 8 dots.
+    #[default]
+    Eight,
+    /// Keep only the 6 dots of traditional braille and strip the bottom row.
+    Six,
+}
+
+
+/// How many of the points of a character are set.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CellState {
+    /// No point is set.
+    Empty,
+    /// Some but not all points are set.
+    Partial,
+    /// All 8 points are set.
+    Full,
+}
+
+
+/// Bit layout of the dot bytes returned by `Canvas::cell_bytes_with_order()`.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum DotOrder {
+    /// The Unicode braille order, same as `Canvas::cell_bytes()`.
+    /// Bits 0 to 5 go down the left then the right column of the top three rows,
+    /// bits 6 and 7 are the bottom left and bottom right dot.
+    #[default]
+    Unicode,
+    /// Row-major order, bit `y * 2 + x` is the dot at x, y inside the character.
+    Linear,
+}
+
+
+/// Decides which neighbouring points count as connected.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Connectivity {
+    /// Only points sharing an edge: left, right, above and below.
+    Four,
+    /// Points sharing an edge or a corner.
+    Eight,
+}
+
+
+/// Decides what `Canvas::with_caption()` does with captions wider than the canvas.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum CaptionOverflow {
+    /// Break the caption into several lines, between words where possible.
+    #[default]
+    Wrap,
+    /// Cut the caption off at the width of the canvas.
+    Truncate,
+}
+
+
+/// Where point 0, 0 of a canvas lies and which way y increases.
+///
+/// The origin is used by `Canvas::set()`, `reset()`, `flip()` and `get()`
+/// and the drawing methods built on top of them, like lines and shapes.
+/// Methods working on characters or returning points, like `char_at()`, `bounds()`,
+/// `retain_points()` or `connected_components()`, always use the top left,
+/// as do `stamp_icon()` and views created by `view_mut()`.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Origin {
+    /// 0, 0 is top left and y increases downwards.
+    #[default]
+    TopLeft,
+    /// 0, 0 is bottom left and y increases upwards, like in most plots.
+    BottomLeft,
+}
+
+
+/// Morphological operation applied by `Canvas::morph()`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MorphOp {
+    /// Keep a point only if every point under the kernel is set, removing speckles.
+    Erode,
+    /// Set a point if any point under the kernel is set, closing gaps.
+    Dilate,
+}
+
+
+/// Saved state of a canvas, created by `Canvas::snapshot()` and applied with `Canvas::restore()`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CanvasSnapshot {
+    x: usize,
+    y: usize,
+    data: Vec<u32>,
+    blink: BTreeSet<usize>,
+    dot_map: [u32; 8],
+    origin: Origin,
+}
+
+
 /// Represents a Canvas that is drawn by braille characters.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Canvas {
@@ -15,14 +279,39 @@ pub struct Canvas {
     char_x: usize,
     char_y: usize,
     data: Vec<u32>,
+    /// Indices into `data` of the characters flagged to blink, kept apart from the data
+    /// so the flag can't collide with the reserved bits.
+    blink: BTreeSet<usize>,
+    dot_map: [u32; 8],
+    strict: bool,
+    origin: Origin,
 }
 
 
 impl Canvas {
+    /// Physical width to height ratio of a single point when rendered.
+    /// In typical terminal fonts a point is roughly half as wide as it is tall.
+    /// The exact value depends on the font, so treat this as an approximation.
+    pub const DOT_ASPECT: f64 = 0.5;
+
+    /// Returns how many points vertically cover the same physical length as
+    /// `dots` points horizontally, according to `DOT_ASPECT`.
+    /// Shapes that should look round can use this to scale their height.
+    pub fn aspect_correct_y(dots: usize) -> usize {
+        (dots as f64 * Canvas::DOT_ASPECT).round() as usize
+    }
+
     /// Returns the index of `self.data` the given xy-coords lie in.
     /// Returns `None` if out of range.
+    #[allow(clippy::needless_return)]
     fn coords_to_index(&self, x: usize, y: usize) -> Option<usize> {
-        let res = x / 2 + self.char_x * (y / 4);
+        debug_assert!(self.is_consistent());
+
+        if x >= self.x || y >= self.y {
+            return None;
+        }
+
+        let res = self.char_x.checked_mul(y / 4)?.checked_add(x / 2)?;
 
         return if res >= self.data.len() {
             None
@@ -31,8 +320,90 @@ impl Canvas {
         };
     }
 
+    /// Converts `y` between the top left and the origin of the canvas.
+    /// Out of range values are returned unchanged, so they still produce an error.
+    pub(crate) fn origin_y(&self, y: usize) -> usize {
+        match self.origin {
+            Origin::BottomLeft if y < self.y => self.y - 1 - y,
+            _ => y,
+        }
+    }
+
+    /// Returns the position of the point shown by `dot` of the character at `index` of `self.data`,
+    /// the inverse of `coords_to_index()`. `dot` indexes the dot table.
+    fn index_to_coords(&self, index: usize, dot: usize) -> (usize, usize) {
+        let (cx, cy) = (index % self.char_x, index / self.char_x);
+
+        (cx * 2 + dot / 4, cy * 4 + dot % 4)
+    }
+
+    /// Returns whether the point at x, y is set, with 0, 0 always being top left.
+    /// Points out of range are not set.
+    fn is_set_top_left(&self, x: usize, y: usize) -> bool {
+        self.coords_to_index(x, y)
+            .is_some_and(|i| self.data[i] & self.dot_map[(y % 4) + (x % 2) * 4] != 0)
+    }
+
+    /// Sets the point at x, y, with 0, 0 always being top left.
+    /// Points out of range are ignored.
+    fn set_top_left(&mut self, x: usize, y: usize) {
+        if let Some(i) = self.coords_to_index(x, y) {
+            self.data[i] |= self.dot_map[(y % 4) + (x % 2) * 4];
+        }
+    }
+
+    /// Returns the bits of the character at `cx`, `cy` whose points lie inside of the canvas,
+    /// according to the dot table.
+    fn cell_mask(&self, cx: usize, cy: usize) -> u32 {
+        (0..8)
+            .filter(|&dot| cx * 2 + dot / 4 < self.x && cy * 4 + dot % 4 < self.y)
+            .fold(0, |mask, dot| mask | self.dot_map[dot])
+    }
+
+    /// Returns the size of the canvas in points.
+    pub(crate) fn size(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    /// Returns whether both canvases have the same size.
+    pub(crate) fn same_size(&self, other: &Canvas) -> bool {
+        self.x == other.x && self.y == other.y
+    }
+
+    /// Returns a blank canvas sized `x` by `y` with the same dot table, origin and strict mode.
+    pub(crate) fn sized_like(&self, x: usize, y: usize) -> Canvas {
+        Canvas {
+            dot_map: self.dot_map,
+            strict: self.strict,
+            origin: self.origin,
+            ..Canvas::with_size(x, y)
+        }
+    }
+
+    /// Returns a blank canvas with the same size, dot table, origin and strict mode.
+    pub(crate) fn blank_like(&self) -> Canvas {
+        self.sized_like(self.x, self.y)
+    }
+
+    /// Returns the data a character needs to show the dots of `glyph`, according to the dot table.
+    fn glyph_to_data(&self, glyph: BrailleChar) -> u32 {
+        (0..8)
+            .filter(|&dot| glyph.get(dot / 4, dot % 4).unwrap())
+            .fold(0, |value, dot| value | self.dot_map[dot])
+    }
+
+    /// Returns an `IndexError` noting the drawing operation `op` if the point is out of range.
+    fn check_point(&self, x: usize, y: usize, op: &'static str) -> Result<(), IndexError> {
+        if x < self.x && y < self.y {
+            Ok(())
+        } else {
+            Err(IndexError::USizeMatrix(self.x, self.y, x, y).in_operation(op))
+        }
+    }
+
     /// Creates a new Canvas sized 0 by 0.
     /// Changing size currently not supported. Use `with_size()` instead.
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Canvas {
             x: 0,
@@ -40,6 +411,10 @@ impl Canvas {
             char_x: 0,
             char_y: 0,
             data: vec![],
+            blink: BTreeSet::new(),
+            dot_map: BYTE_MASK,
+            strict: false,
+            origin: Origin::TopLeft,
         }
     }
 
@@ -51,157 +426,3610 @@ impl Canvas {
         let char_x = x / 2 + min(x % 2, 1);
         let char_y = y / 4 + min(y % 4, 1);
 
-        Canvas {
+        let canvas = Canvas {
             x,
             y,
             char_x,
             char_y,
             data: vec![0u32; char_x * char_y],
-        }
+            blink: BTreeSet::new(),
+            dot_map: BYTE_MASK,
+            strict: false,
+            origin: Origin::TopLeft,
+        };
+        debug_assert!(canvas.is_consistent());
+
+        canvas
     }
 
-    /// Flips the point at a given x, y position. 0, 0 is top left.
-    /// If coordinates are out of range returns an `IndexError` otherwise `OK()`.
-    pub fn flip(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
-        return if let Some(i) = self.coords_to_index(x, y) {
-            self.data[i] ^= BYTE_MASK[(y % 4) + (x % 2) * 4];
+    /// Creates a new Canvas with size `x` by `y` like `with_size()`,
+    /// but with point 0, 0 at the given `origin`. See `Origin` for which methods this affects.
+    pub fn with_origin(x: usize, y: usize, origin: Origin) -> Self {
+        let mut canvas = Canvas::with_size(x, y);
+        canvas.origin = origin;
 
-            Ok(())
-        } else {
-            Err(IndexError::USizeMatrix(self.x, self.y, x, y))
-        };
+        canvas
     }
 
-    /// Resets the point at a given x, y position. 0, 0 is top left.
-    /// If coordinates are out of range returns an `IndexError` otherwise `OK()`.
-    pub fn reset(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
-        return if let Some(i) = self.coords_to_index(x, y) {
-            self.data[i] &= BYTE_MASK_INVERTED[(y % 4) + (x % 2) * 4];
+    /// Creates a new Canvas with size `x` by `y` like `with_size()`,
+    /// but reserves room for `reserve_cells` additional characters.
+    /// The reserved capacity doesn't change the size of the canvas,
+    /// it only avoids reallocating once the canvas grows.
+    pub fn with_capacity(x: usize, y: usize, reserve_cells: usize) -> Self {
+        let mut canvas = Canvas::with_size(x, y);
+        canvas.data.reserve_exact(reserve_cells);
 
-            Ok(())
-        } else {
-            Err(IndexError::USizeMatrix(self.x, self.y, x, y))
-        };
+        canvas
     }
 
-    /// Resets the whole canvas.
-    pub fn reset_all(&mut self) {
-        self.data = self.data.iter().map(|&v| v & !0b11111111).collect();
+    /// Creates a new Canvas that is exactly `char_x` characters wide and `char_y` characters tall.
+    /// This is the same as calling `with_size()` with `2 * char_x` and `4 * char_y`,
+    /// since every character displays 2*4 points.
+    /// Unlike `with_size()` with sizes that aren't a multiple of 2 and 4,
+    /// every character is fully usable.
+    pub fn with_char_size(char_x: usize, char_y: usize) -> Self {
+        Canvas::with_size(2 * char_x, 4 * char_y)
     }
 
-    /// Sets the point at a given x, y position. 0, 0 is top left.
-    /// If coordinates are out of range returns an `IndexError` otherwise `OK()`.
-    pub fn set(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
-        return if let Some(i) = self.coords_to_index(x, y) {
-            self.data[i] |= BYTE_MASK[(y % 4) + (x % 2) * 4];
+    /// Creates a new Canvas that is exactly `cols` characters wide and `rows` characters tall,
+    /// same as `with_char_size()`.
+    /// Useful for filling a terminal of the given size.
+    pub fn for_terminal(cols: usize, rows: usize) -> Self {
+        Canvas::with_char_size(cols, rows)
+    }
 
-            Ok(())
-        } else {
-            Err(IndexError::USizeMatrix(self.x, self.y, x, y))
+    /// Creates a new Canvas from a string of braille characters, one line per character row.
+    /// Lines shorter than the longest one are padded with blank characters.
+    /// `mode` decides whether the bottom row of dots is kept or stripped.
+    /// If the string contains anything other than braille characters and line breaks
+    /// returns an `IndexError`.
+    pub fn from_str_grid(s: &str, mode: DotMode) -> Result<Canvas, IndexError> {
+        let lines: Vec<&str> = s.lines().collect();
+        let cols = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        let mask = match mode {
+            DotMode::Eight => 0b11111111,
+            DotMode::Six => 0b00111111,
         };
+
+        let mut canvas = Canvas::for_terminal(cols, lines.len());
+
+        for (cy, line) in lines.iter().enumerate() {
+            for (cx, c) in line.chars().enumerate() {
+                let value = c as u32;
+
+                if !(0x2800..=0x28ff).contains(&value) {
+                    return Err(IndexError::InvalidFormat("not a braille character"));
+                }
+
+                canvas.data[cx + cy * cols] = value & mask;
+            }
+        }
+
+        Ok(canvas)
     }
-}
 
+    /// Creates a new Canvas of size `width` by `height` plotting `y = f(x)`.
+    /// `x` is sampled once per column of points, evenly spread from the start to the end of `domain`.
+    /// Every sample gets mapped into `range` with the smallest value at the bottom,
+    /// and consecutive samples are connected by lines. Values outside of `range` get clamped.
+    /// If `range` is `None` it is chosen with `plot::autoscale()` to fit all samples.
+    /// Samples where `f` isn't finite are left out, leaving a gap in the curve.
+    pub fn from_function<F: Fn(f64) -> f64>(
+        width: usize,
+        height: usize,
+        f: F,
+        domain: (f64, f64),
+        range: Option<(f64, f64)>,
+    ) -> Canvas {
+        let mut canvas = Canvas::with_size(width, height);
 
-impl Display for Canvas {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut string = String::with_capacity(self.x * self.y);
+        if height == 0 {
+            return canvas;
+        }
 
-        for (i, value) in self.data.iter().enumerate() {
-            string.push(char::from_u32(0x00002800 | (value & 0b11111111u32)).unwrap());
+        let samples: Vec<f64> = (0..width)
+            .map(|i| {
+                let t = if width > 1 { i as f64 / (width - 1) as f64 } else { 0.0 };
+                f(domain.0 + (domain.1 - domain.0) * t)
+            })
+            .collect();
+        let (min, max) = range.unwrap_or_else(|| plot::autoscale(&samples));
 
-            if i % self.char_x == self.char_x - 1 {
-                string.push('\n');
-            };
+        let mut last: Option<(isize, isize)> = None;
+
+        for (x, &value) in samples.iter().enumerate() {
+            if !value.is_finite() {
+                last = None;
+                continue;
+            }
+
+            let y = height - 1 - plot::map_value(value, min, max, height);
+            let point = (x as isize, y as isize);
+            let (x0, y0) = last.unwrap_or(point);
+
+            canvas.line_clipped(x0, y0, point.0, point.1);
+            last = Some(point);
         }
 
-        write!(f, "{}", string.trim())
+        canvas
     }
-}
 
+    /// Creates a new Canvas of size `width` by `height` from a 1 bit per point bitmap.
+    /// `bits` holds the points in row-major order packed 8 per byte, the most significant bit first.
+    /// Rows are not padded to full bytes.
+    /// If `bits` is too short to cover every point returns an `IndexError`.
+    pub fn from_packed_bits(width: usize, height: usize, bits: &[u8]) -> Result<Canvas, IndexError> {
+        let points = width.checked_mul(height).ok_or(IndexError::InvalidFormat("bitmap too large"))?;
 
-#[cfg(test)]
-pub mod tests {
-    use super::*;
+        if bits.len() < points.div_ceil(8) {
+            return Err(IndexError::InvalidFormat("not enough bits for the given size"));
+        }
 
-    #[test]
-    fn canvas_with_size() {
-        let c = Canvas::with_size(3, 6);
-        dbg!(c.to_string());
+        let mut canvas = Canvas::with_size(width, height);
 
-        let c = Canvas::with_size(2, 4);
-        dbg!(c.to_string());
+        for y in 0..height {
+            let row = y * width;
+            let cells = &mut canvas.data[(y / 4) * canvas.char_x..];
 
-        let c = Canvas::with_size(4, 4);
-        dbg!(c.to_string());
+            for x in 0..width {
+                let i = row + x;
 
-        let c = Canvas::with_size(7, 10);
-        dbg!(c);
+                if bits[i / 8] & (0b10000000 >> (i % 8)) != 0 {
+                    cells[x / 2] |= BYTE_MASK[(y % 4) + (x % 2) * 4];
+                }
+            }
+        }
+
+        Ok(canvas)
     }
 
-    #[test]
-    fn canvas_set() {
-        let mut c = Canvas::with_size(7, 10);
-        println!("{c} {} {}", c.char_x, c.char_y);
+    /// Creates a new Canvas with size `x` by `y` like `with_size()`, but with a custom dot table.
+    ///
+    /// The dot table decides which bit of a character gets changed by each point inside of it.
+    /// It is indexed by `y + 4 * x` where x and y are the position inside the character:
+    ///
+    /// `(0) (4)`
+    ///
+    /// `(1) (5)`
+    ///
+    /// `(2) (6)`
+    ///
+    /// `(3) (7)`
+    ///
+    /// The default table follows the Unicode standard.
+    /// This is useful for fonts or hardware that expect a different bit order.
+    /// The table needs to use each of the lowest 8 bit exactly once,
+    /// otherwise returns an `IndexError`.
+    pub fn with_dot_map(x: usize, y: usize, map: [u32; 8]) -> Result<Canvas, IndexError> {
+        let used = map.iter().fold(0, |used, &bit| used | bit);
 
-        c.set(0, 0).unwrap();
-        c.set(6, 9).unwrap();
-        c.set(6, 0).unwrap();
-        c.set(0, 9).unwrap();
-        println!("{c}");
+        if used != 0b11111111 || map.iter().any(|bit| bit.count_ones() != 1) {
+            return Err(IndexError::InvalidArgument("dot map must use each of the lowest 8 bit once"));
+        }
+
+        let mut canvas = Canvas::with_size(x, y);
+        canvas.dot_map = map;
+
+        Ok(canvas)
     }
 
-    #[test]
-    fn canvas_reset() {
-        let mut c = Canvas::with_size(7, 10);
-        println!("{c} {} {}", c.char_x, c.char_y);
+    /// Combines the given layers into a new canvas by setting every point that is set in any of them.
+    /// Layers are combined in order, so the reserved upper bits of later layers win.
+    /// Characters flagged to blink in any layer blink in the result.
+    /// The result uses the dot table of the first layer, layers with another one get translated.
+    /// All layers need to be the same size,
+    /// otherwise returns an `IndexError` naming the first layer that isn't.
+    /// An empty slice of layers results in an empty canvas.
+    pub fn composite(layers: &[&Canvas]) -> Result<Canvas, IndexError> {
+        let Some(first) = layers.first() else {
+            return Ok(Canvas::new());
+        };
 
-        c.set(0, 0).unwrap();
-        c.set(6, 9).unwrap();
-        c.set(6, 0).unwrap();
-        c.set(0, 9).unwrap();
-        println!("{c}");
+        let mut canvas = first.blank_like();
 
-        c.reset(0, 0).unwrap();
-        println!("{c}");
-        c.reset(6, 9).unwrap();
-        println!("{c}");
-        c.reset(6, 0).unwrap();
-        println!("{c}");
-        c.reset(0, 9).unwrap();
-        println!("{c}");
+        for (i, layer) in layers.iter().enumerate() {
+            if layer.x != canvas.x || layer.y != canvas.y {
+                return Err(IndexError::LayerMismatch(i, canvas.x, canvas.y, layer.x, layer.y));
+            }
+
+            for (value, &other) in canvas.data.iter_mut().zip(layer.data.iter()) {
+                let dots = (0..8)
+                    .filter(|&dot| other & layer.dot_map[dot] != 0)
+                    .fold(0, |dots, dot| dots | canvas.dot_map[dot]);
+                let upper = if other >> 8 != 0 { other } else { *value };
+                *value = (upper & !0b11111111) | ((*value | dots) & 0b11111111);
+            }
+
+            canvas.blink.extend(&layer.blink);
+        }
+
+        Ok(canvas)
     }
 
-    #[test]
-    fn canvas_reset_all() {
-        let mut c = Canvas::with_size(7, 10);
-        println!("{c} {} {}", c.char_x, c.char_y);
+    /// Returns a canvas half the size, where every point is set if any point
+    /// of the matching 2x2 block of this canvas is set.
+    /// Odd sizes are rounded up, the blocks at the edge just use the points that exist.
+    pub fn downsample_2x(&self) -> Canvas {
+        let (x, y) = (self.x / 2 + self.x % 2, self.y / 2 + self.y % 2);
+        let mut canvas = self.sized_like(x, y);
 
-        c.set(0, 0).unwrap();
-        c.set(6, 9).unwrap();
-        c.set(6, 0).unwrap();
-        c.set(0, 9).unwrap();
-        println!("{c}");
+        for (x, y) in self.set_points() {
+            canvas.set_top_left(x / 2, y / 2);
+        }
 
-        c.reset_all();
-        println!("{c}");
+        canvas
     }
 
-    #[test]
-    fn canvas_flip() {
-        let mut c = Canvas::with_size(7, 10);
-        println!("{c} {} {}", c.char_x, c.char_y);
+    /// Returns a copy of the canvas where the top left quarter is mirrored into the other three,
+    /// horizontally into the top right, vertically into the bottom left and both ways into the
+    /// bottom right. Everything outside of the top left quarter gets replaced.
+    /// For an odd width or height the center column or row belongs to the top left quarter
+    /// and stays as it is. Only the points are carried over, the reserved upper bits are not.
+    pub fn kaleidoscope(&self) -> Canvas {
+        let mut canvas = self.blank_like();
+        let (half_x, half_y) = (self.x.div_ceil(2), self.y.div_ceil(2));
 
-        c.flip(0, 0).unwrap();
-        c.flip(6, 9).unwrap();
-        c.flip(6, 0).unwrap();
-        c.flip(0, 9).unwrap();
-        println!("{c}");
+        for (x, y) in self.set_points().filter(|&(x, y)| x < half_x && y < half_y) {
+            for (x, y) in [(x, y), (self.x - 1 - x, y), (x, self.y - 1 - y), (self.x - 1 - x, self.y - 1 - y)] {
+                canvas.set_top_left(x, y);
+            }
+        }
 
-        c.flip(0, 0).unwrap();
-        c.flip(6, 9).unwrap();
-        c.flip(6, 0).unwrap();
-        c.flip(0, 9).unwrap();
-        println!("{c}");
+        canvas
+    }
+
+    /// Returns a copy of the canvas with the morphological operation `op` applied to its points.
+    /// `kernel` is the 3*3 structuring element centered on each point, indexed as `kernel[y][x]`.
+    /// Points outside of the canvas count as not set.
+    /// Only the points are carried over, the reserved upper bits are not.
+    pub fn morph(&self, kernel: &[[bool; 3]; 3], op: MorphOp) -> Canvas {
+        let mut canvas = self.blank_like();
+        let is_set = |x: usize, y: usize, dx: usize, dy: usize| match ((x + dx).checked_sub(1), (y + dy).checked_sub(1)) {
+            (Some(x), Some(y)) => self.is_set_top_left(x, y),
+            _ => false,
+        };
+
+        for y in 0..self.y {
+            for x in 0..self.x {
+                let mut covered = (0..3)
+                    .flat_map(|dy| (0..3).map(move |dx| (dx, dy)))
+                    .filter(|&(dx, dy)| kernel[dy][dx]);
+
+                let set = match op {
+                    MorphOp::Erode => covered.all(|(dx, dy)| is_set(x, y, dx, dy)),
+                    MorphOp::Dilate => covered.any(|(dx, dy)| is_set(x, y, dx, dy)),
+                };
+
+                if set {
+                    canvas.set_top_left(x, y);
+                }
+            }
+        }
+
+        canvas
+    }
+
+    /// Returns a copy of the canvas that only keeps the set points with at least one of their
+    /// 4 direct neighbours not set, turning filled shapes into their outline.
+    /// Points outside of the canvas count as not set, so shapes touching the edge stay closed.
+    /// Only the points are carried over, the reserved upper bits are not.
+    pub fn outline(&self) -> Canvas {
+        let cross = [[false, true, false], [true, true, true], [false, true, false]];
+        let inner = self.morph(&cross, MorphOp::Erode);
+        let mut canvas = self.blank_like();
+
+        for (x, y) in self.set_points().filter(|&(x, y)| !inner.is_set_top_left(x, y)) {
+            canvas.set_top_left(x, y);
+        }
+
+        canvas
+    }
+
+    /// Returns a copy of the canvas rotated by 90 degrees clockwise.
+    /// Width and height get swapped. Only the points are carried over, the reserved upper bits are not.
+    pub fn rotate_90_cw(&self) -> Canvas {
+        let mut canvas = self.sized_like(self.y, self.x);
+
+        for (x, y) in self.set_points() {
+            canvas.set_top_left(self.y - 1 - y, x);
+        }
+
+        canvas
+    }
+
+    /// Returns a copy of the canvas rotated by 90 degrees counterclockwise.
+    /// Width and height get swapped. Only the points are carried over, the reserved upper bits are not.
+    pub fn rotate_90_ccw(&self) -> Canvas {
+        let mut canvas = self.sized_like(self.y, self.x);
+
+        for (x, y) in self.set_points() {
+            canvas.set_top_left(y, self.x - 1 - x);
+        }
+
+        canvas
+    }
+
+    /// Returns a new canvas with every point set that is set in exactly one of the two canvases.
+    /// Useful to highlight what differs between two frames or shapes.
+    /// If the canvases differ in size returns an `IndexError`.
+    pub fn symmetric_difference(&self, other: &Canvas) -> Result<Canvas, IndexError> {
+        if self.x != other.x || self.y != other.y {
+            return Err(IndexError::SizeMismatch(self.x, self.y, other.x, other.y));
+        }
+
+        let mut canvas = self.blank_like();
+
+        for (value, (a, b)) in canvas.data.iter_mut().zip(self.data.iter().zip(other.data.iter())) {
+            *value = (a ^ b) & 0b11111111;
+        }
+
+        Ok(canvas)
+    }
+
+    /// Returns a new canvas showing which points changed between the two canvases,
+    /// the same as `symmetric_difference()`.
+    /// If the canvases differ in size returns an `IndexError`.
+    pub fn xor_into_new(&self, other: &Canvas) -> Result<Canvas, IndexError> {
+        self.symmetric_difference(other)
+    }
+
+    /// Returns a new canvas of the same size where every set point is moved to the position `f` returns.
+    /// Positions are relative to the origin of the canvas, see `with_origin()`.
+    /// Points for which `f` returns `None` or a position out of range are dropped.
+    pub fn map_points<F: Fn(usize, usize) -> Option<(usize, usize)>>(&self, f: F) -> Canvas {
+        let mut canvas = self.blank_like();
+
+        for (x, y) in self.set_points() {
+            if let Some((x, y)) = f(x, self.origin_y(y)) {
+                let _ = canvas.set(x, y);
+            }
+        }
+
+        canvas
+    }
+
+    /// Calls `f` with mutable access to the raw data of every character in row-major order.
+    ///
+    /// The lowest 8 bit hold the points in the order of the canvas' dot table,
+    /// see `with_dot_map()`. By default that is the Unicode braille order,
+    /// where bit 0 is the top left point and bit 7 the bottom right:
+    ///
+    /// `(0) (3)`
+    ///
+    /// `(1) (4)`
+    ///
+    /// `(2) (5)`
+    ///
+    /// `(6) (7)`
+    ///
+    /// The upper 24 bit are reserved, currently for a `0xRRGGBB` color used by `to_html()`.
+    /// The blink flag of `set_blink()` isn't part of the data.
+    pub fn for_each_cell_mut<F: FnMut(&mut u32)>(&mut self, f: F) {
+        self.data.iter_mut().for_each(f);
+    }
+
+    /// Moves every point `dx` points to the right and `dy` points down,
+    /// crossing character boundaries as needed. Points moved off the canvas are dropped
+    /// and the uncovered edges are left blank.
+    /// Meant for smooth animation by less than a character, so `dx` up to 1 and `dy` up to 3,
+    /// but larger amounts work as well.
+    pub fn scroll_subcell(&mut self, dx: usize, dy: usize) {
+        let points: Vec<(usize, usize)> = self.set_points().collect();
+        self.reset_all();
+
+        for (x, y) in points {
+            if let (Some(x), Some(y)) = (x.checked_add(dx), y.checked_add(dy)) {
+                let _ = self.set(x, self.origin_y(y));
+            }
+        }
+    }
+
+    /// Resets every set point for which `f` returns `false` and keeps the rest.
+    /// Only set points are passed to `f`.
+    pub fn retain_points<F: Fn(usize, usize) -> bool>(&mut self, f: F) {
+        let dropped: Vec<(usize, usize)> = self.set_points().filter(|&(x, y)| !f(x, y)).collect();
+
+        for (x, y) in dropped {
+            self.reset(x, self.origin_y(y)).unwrap();
+        }
+    }
+
+    /// Sets every point of `src` shifted by `dx`, `dy` on this canvas, dropping points out of range.
+    fn blit(&mut self, src: &Canvas, dx: usize, dy: usize) {
+        for (x, y) in src.set_points() {
+            let _ = self.set(x + dx, self.origin_y(y + dy));
+        }
+    }
+
+    /// Sets every point of `src` shifted right by `dx` on this canvas, starting in the top left.
+    /// Points shifted past the right edge wrap around and continue from the left edge,
+    /// which makes scrolling a ticker a matter of increasing `dx` every frame.
+    /// Existing points are kept.
+    /// If both canvases aren't the same height returns an `IndexError` otherwise `OK()`.
+    pub fn blit_wrap_x(&mut self, src: &Canvas, dx: usize) -> Result<(), IndexError> {
+        if src.y != self.y {
+            return Err(IndexError::SizeMismatch(self.x, self.y, src.x, src.y).in_operation("blit_wrap_x"));
+        }
+
+        if self.x == 0 {
+            return Ok(());
+        }
+
+        for (x, y) in src.set_points() {
+            let x = (x % self.x + dx % self.x) % self.x;
+            self.set(x, self.origin_y(y))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns a copy of the canvas surrounded by blank margins, given in points.
+    /// The new canvas is `left + right` points wider and `top + bottom` points taller,
+    /// with every point moved right by `left` and down by `top`.
+    /// Only the points are carried over, the reserved upper bits are not.
+    pub fn padded(&self, left: usize, right: usize, top: usize, bottom: usize) -> Canvas {
+        let mut canvas = self.sized_like(self.x + left + right, self.y + top + bottom);
+        canvas.blit(self, left, top);
+
+        canvas
+    }
+
+    /// Returns a canvas of size `target_w` by `target_h` filled by repeating the points of this canvas,
+    /// starting in the top left. Repetitions at the right and bottom edges get cut off.
+    /// Repeating an empty canvas gives a blank canvas.
+    pub fn tiled(&self, target_w: usize, target_h: usize) -> Canvas {
+        let mut canvas = self.sized_like(target_w, target_h);
+
+        if self.x == 0 || self.y == 0 {
+            return canvas;
+        }
+
+        for y in (0..target_h).step_by(self.y) {
+            for x in (0..target_w).step_by(self.x) {
+                canvas.blit(self, x, y);
+            }
+        }
+
+        canvas
+    }
+
+    /// Splits the canvas into tiles of `tile_w` by `tile_h` points, returned in row-major order.
+    /// If the size of the canvas isn't a multiple of the tile size, the tiles of the last
+    /// column and row stick out of the canvas and are padded with points that aren't set,
+    /// so every tile has the same size. A tile size of 0 gives no tiles.
+    /// Only the points are carried over, the reserved upper bits are not.
+    pub fn tiles(&self, tile_w: usize, tile_h: usize) -> Vec<Canvas> {
+        if tile_w == 0 || tile_h == 0 {
+            return Vec::new();
+        }
+
+        let cols = self.x.div_ceil(tile_w);
+        let rows = self.y.div_ceil(tile_h);
+        let mut tiles = vec![self.sized_like(tile_w, tile_h); cols * rows];
+
+        for (x, y) in self.set_points() {
+            tiles[x / tile_w + y / tile_h * cols].set_top_left(x % tile_w, y % tile_h);
+        }
+
+        tiles
+    }
+
+    /// Packs the given sprites into one canvas, laid out in a grid with `columns` sprites per row
+    /// and `padding` blank points between them. A `columns` of 0 is treated as 1.
+    /// Every grid cell is as large as the largest sprite and sprites sit in the top left of their cell.
+    ///
+    /// Returns the combined canvas and the placement of each sprite as `(x, y, width, height)`
+    /// in the same order as `sprites`.
+    pub fn atlas(
+        sprites: &[&Canvas],
+        columns: usize,
+        padding: usize,
+    ) -> (Canvas, Vec<(usize, usize, usize, usize)>) {
+        if sprites.is_empty() {
+            return (Canvas::new(), vec![]);
+        }
+
+        let columns = columns.clamp(1, sprites.len());
+        let rows = sprites.len().div_ceil(columns);
+        let cell_w = sprites.iter().map(|s| s.x).max().unwrap();
+        let cell_h = sprites.iter().map(|s| s.y).max().unwrap();
+
+        let mut canvas = Canvas::with_size(
+            columns * cell_w + (columns - 1) * padding,
+            rows * cell_h + (rows - 1) * padding,
+        );
+        let mut placements = Vec::with_capacity(sprites.len());
+
+        for (i, sprite) in sprites.iter().enumerate() {
+            let x = (i % columns) * (cell_w + padding);
+            let y = (i / columns) * (cell_h + padding);
+
+            canvas.blit(sprite, x, y);
+            placements.push((x, y, sprite.x, sprite.y));
+        }
+
+        (canvas, placements)
+    }
+
+    /// Returns every group of connected set points, using `connectivity` to decide which
+    /// neighbours are connected.
+    /// Groups are ordered by their first point in row-major order, which is also their first entry.
+    /// Combine with `bounds()` on a canvas of a single group to get its bounding box.
+    pub fn connected_components(&self, connectivity: Connectivity) -> Vec<Vec<(usize, usize)>> {
+        let neighbours: &[(isize, isize)] = match connectivity {
+            Connectivity::Four => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Connectivity::Eight => &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)],
+        };
+
+        let mut visited = vec![false; self.x * self.y];
+        let mut components = Vec::new();
+
+        for y in 0..self.y {
+            for x in 0..self.x {
+                if visited[x + y * self.x] || !self.get(x, self.origin_y(y)).unwrap() {
+                    continue;
+                }
+
+                visited[x + y * self.x] = true;
+                let mut component = vec![(x, y)];
+                let mut next = 0;
+
+                while let Some(&(px, py)) = component.get(next) {
+                    next += 1;
+
+                    for &(dx, dy) in neighbours {
+                        let (Some(nx), Some(ny)) = (px.checked_add_signed(dx), py.checked_add_signed(dy)) else {
+                            continue;
+                        };
+
+                        if nx < self.x && ny < self.y && !visited[nx + ny * self.x] && self.get(nx, self.origin_y(ny)).unwrap() {
+                            visited[nx + ny * self.x] = true;
+                            component.push((nx, ny));
+                        }
+                    }
+                }
+
+                components.push(component);
+            }
+        }
+
+        components
+    }
+
+    /// Saves the current points, reserved bits and blink flags, for example to implement undo.
+    /// The dot table and origin are saved as well, since the points can't be read without them.
+    pub fn snapshot(&self) -> CanvasSnapshot {
+        CanvasSnapshot {
+            x: self.x,
+            y: self.y,
+            data: self.data.clone(),
+            blink: self.blink.clone(),
+            dot_map: self.dot_map,
+            origin: self.origin,
+        }
+    }
+
+    /// Restores the points, reserved bits, blink flags, dot table and origin saved in `snap`.
+    /// If the snapshot was taken from a canvas of a different size returns an `IndexError`.
+    pub fn restore(&mut self, snap: &CanvasSnapshot) -> Result<(), IndexError> {
+        if snap.x != self.x || snap.y != self.y {
+            return Err(IndexError::SizeMismatch(self.x, self.y, snap.x, snap.y));
+        }
+
+        self.data.copy_from_slice(&snap.data);
+        self.blink.clone_from(&snap.blink);
+        self.dot_map = snap.dot_map;
+        self.origin = snap.origin;
+
+        Ok(())
+    }
+
+    /// Checks that the point at x, y, with 0, 0 always being top left, is stored where it belongs:
+    /// setting it on a copy of the canvas makes it read back as set, and the bit that changed
+    /// maps back to the same x, y. Returns `false` if x, y is out of range.
+    /// Meant for testing the indexing of the canvas.
+    pub fn dot_roundtrip(&self, x: usize, y: usize) -> bool {
+        let Some(i) = self.coords_to_index(x, y) else {
+            return false;
+        };
+
+        let mut canvas = self.blank_like();
+        canvas.data[i] |= canvas.dot_map[(y % 4) + (x % 2) * 4];
+
+        let changed: Vec<(usize, usize)> = (0..8)
+            .filter(|&dot| canvas.data[i] & canvas.dot_map[dot] != 0)
+            .map(|dot| canvas.index_to_coords(i, dot))
+            .collect();
+
+        canvas.is_set_top_left(x, y) && changed == [(x, y)]
+    }
+
+    /// Returns whether the internal dimensions of the canvas agree with each other.
+    /// That is the amount of characters matches the size in points and the stored data.
+    /// This should always be `true`, if it isn't the canvas got corrupted.
+    pub fn is_consistent(&self) -> bool {
+        self.char_x == self.x / 2 + min(self.x % 2, 1)
+            && self.char_y == self.y / 4 + min(self.y % 4, 1)
+            && self.char_x.checked_mul(self.char_y) == Some(self.data.len())
+    }
+
+    /// Returns the amount of characters per row.
+    pub fn char_x(&self) -> usize {
+        self.char_x
+    }
+
+    /// Returns the amount of character rows.
+    pub fn char_y(&self) -> usize {
+        self.char_y
+    }
+
+    /// Returns how many terminal columns the rendered canvas takes up in a monospace font.
+    /// Every braille character is one column wide, so this is the amount of characters per row.
+    /// Rendering only trims the line break after the last row, so the width is never reduced.
+    pub fn display_width(&self) -> usize {
+        self.char_x
+    }
+
+    /// Returns the dot byte of every character in row-major order in the Unicode braille order,
+    /// for braille hardware that takes the dot bytes directly.
+    /// Use `char_x()` and `char_y()` to split them back into rows.
+    /// Unlike `to_bytes()` there is no header.
+    pub fn cell_bytes(&self) -> Vec<u8> {
+        self.cell_bytes_with_order(DotOrder::Unicode)
+    }
+
+    /// Returns the dot byte of every character in row-major order like `cell_bytes()`,
+    /// but with the dots arranged in the bit layout `order`, for hardware that doesn't use
+    /// the Unicode dot numbering.
+    pub fn cell_bytes_with_order(&self, order: DotOrder) -> Vec<u8> {
+        let bit = |dot: usize| match order {
+            DotOrder::Unicode => BYTE_MASK[dot] as u8,
+            DotOrder::Linear => 1 << ((dot % 4) * 2 + dot / 4),
+        };
+
+        self.data
+            .iter()
+            .map(|&value| {
+                (0..8)
+                    .filter(|&dot| value & self.dot_map[dot] != 0)
+                    .fold(0u8, |byte, dot| byte | bit(dot))
+            })
+            .collect()
+    }
+
+    /// Returns whether the point at a given x, y position is set. 0, 0 is top left unless set otherwise by `with_origin()`.
+    /// If coordinates are out of range returns an `IndexError`.
+    pub fn get(&self, x: usize, y: usize) -> Result<bool, IndexError> {
+        let oy = self.origin_y(y);
+
+        if let Some(i) = self.coords_to_index(x, oy) {
+            Ok(self.data[i] & self.dot_map[(oy % 4) + (x % 2) * 4] != 0)
+        } else {
+            Err(IndexError::USizeMatrix(self.x, self.y, x, y))
+        }
+    }
+
+    /// Returns whether each point of column `x` is set, from top to bottom.
+    /// If `x` is out of range returns an `IndexError`.
+    pub fn column_bits(&self, x: usize) -> Result<Vec<bool>, IndexError> {
+        if x >= self.x {
+            return Err(IndexError::USizeMatrix(self.x, self.y, x, 0));
+        }
+
+        let column: Vec<u32> = (0..self.char_y).map(|cy| self.data[x / 2 + cy * self.char_x]).collect();
+
+        Ok((0..self.y)
+            .map(|y| column[y / 4] & self.dot_map[(y % 4) + (x % 2) * 4] != 0)
+            .collect())
+    }
+
+    /// Returns whether each point of row `y` is set, from left to right.
+    /// If `y` is out of range returns an `IndexError`.
+    pub fn row_bits(&self, y: usize) -> Result<Vec<bool>, IndexError> {
+        if y >= self.y {
+            return Err(IndexError::USizeMatrix(self.x, self.y, 0, y));
+        }
+
+        let row = &self.data[(y / 4) * self.char_x..(y / 4 + 1) * self.char_x];
+
+        Ok((0..self.x)
+            .map(|x| row[x / 2] & self.dot_map[(y % 4) + (x % 2) * 4] != 0)
+            .collect())
+    }
+
+    /// Returns whether the character at `cx`, `cy` is empty, partially set or full.
+    /// If the character position is out of range returns an `IndexError`.
+    pub fn cell_state(&self, cx: usize, cy: usize) -> Result<CellState, IndexError> {
+        if cx >= self.char_x || cy >= self.char_y {
+            return Err(IndexError::USizeMatrix(self.char_x, self.char_y, cx, cy));
+        }
+
+        Ok(match (self.data[cx + cy * self.char_x] & 0b11111111).count_ones() {
+            0 => CellState::Empty,
+            8 => CellState::Full,
+            _ => CellState::Partial,
+        })
+    }
+
+    /// Returns the indices of all character rows without any set point.
+    pub fn empty_rows(&self) -> Vec<usize> {
+        (0..self.char_y)
+            .filter(|&cy| {
+                self.data[cy * self.char_x..(cy + 1) * self.char_x]
+                    .iter()
+                    .all(|v| v & 0b11111111 == 0)
+            })
+            .collect()
+    }
+
+    /// Returns the indices of all character columns without any set point.
+    pub fn empty_cols(&self) -> Vec<usize> {
+        (0..self.char_x)
+            .filter(|&cx| (0..self.char_y).all(|cy| self.data[cx + cy * self.char_x] & 0b11111111 == 0))
+            .collect()
+    }
+
+    /// Returns an iterator over the positions of all set points, going through the canvas
+    /// character by character. Empty characters are skipped without looking at their points.
+    fn set_points(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(_, &value)| value & 0b11111111 != 0)
+            .flat_map(move |(i, &value)| {
+                (0..8).filter_map(move |dot| {
+                    let (x, y) = self.index_to_coords(i, dot);
+
+                    if value & self.dot_map[dot] != 0 && x < self.x && y < self.y {
+                        Some((x, y))
+                    } else {
+                        None
+                    }
+                })
+            })
+    }
+
+    /// Returns the average position of all set points as `(x, y)`, measured in points
+    /// with 0, 0 being top left. Returns `None` if no point is set.
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        let (count, sum_x, sum_y) = self.set_points().fold((0usize, 0.0, 0.0), |(count, sum_x, sum_y), (x, y)| {
+            (count + 1, sum_x + x as f64, sum_y + y as f64)
+        });
+
+        if count == 0 {
+            None
+        } else {
+            Some((sum_x / count as f64, sum_y / count as f64))
+        }
+    }
+
+    /// Returns whether any set point of `other`, shifted by `dx`, `dy`, is also set on this canvas.
+    /// 0, 0 is top left. Points shifted out of range never collide.
+    /// Stops at the first overlapping point.
+    pub fn intersects(&self, other: &Canvas, dx: usize, dy: usize) -> bool {
+        other.set_points().any(|(x, y)| {
+            let (Some(x), Some(y)) = (x.checked_add(dx), y.checked_add(dy)) else {
+                return false;
+            };
+
+            self.is_set_top_left(x, y)
+        })
+    }
+
+    /// Returns the bounding box of all set points as `(min_x, min_y, max_x, max_y)`.
+    /// Returns `None` if no point is set.
+    pub fn bounds(&self) -> Option<(usize, usize, usize, usize)> {
+        self.set_points().fold(None, |bounds, (x, y)| match bounds {
+            None => Some((x, y, x, y)),
+            Some((min_x, min_y, max_x, max_y)) => {
+                Some((min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)))
+            }
+        })
+    }
+
+    /// Flips the point at a given x, y position. 0, 0 is top left unless set otherwise by `with_origin()`.
+    /// If coordinates are out of range returns an `IndexError` otherwise `OK()`.
+    #[allow(clippy::needless_return)]
+    pub fn flip(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
+        let oy = self.origin_y(y);
+
+        return if let Some(i) = self.coords_to_index(x, oy) {
+            self.data[i] ^= self.dot_map[(oy % 4) + (x % 2) * 4];
+
+            Ok(())
+        } else {
+            Err(IndexError::USizeMatrix(self.x, self.y, x, y))
+        };
+    }
+
+    /// Resets the point at a given x, y position. 0, 0 is top left unless set otherwise by `with_origin()`.
+    /// If coordinates are out of range returns an `IndexError` otherwise `OK()`.
+    #[allow(clippy::needless_return)]
+    pub fn reset(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
+        let oy = self.origin_y(y);
+
+        return if let Some(i) = self.coords_to_index(x, oy) {
+            self.data[i] &= !self.dot_map[(oy % 4) + (x % 2) * 4];
+
+            Ok(())
+        } else {
+            Err(IndexError::USizeMatrix(self.x, self.y, x, y))
+        };
+    }
+
+    /// Resets the whole canvas.
+    /// The size stays the same, use `clear()` to also drop the size.
+    pub fn reset_all(&mut self) {
+        self.data = self.data.iter().map(|&v| v & !0b11111111).collect();
+    }
+
+    /// Turns the canvas back into the 0 by 0 state of `new()` and frees its data.
+    /// Unlike `reset_all()`, which only resets the points, this also drops the size.
+    /// The dot table set by `with_dot_map()`, the origin and strict mode are kept.
+    pub fn clear(&mut self) {
+        *self = Canvas {
+            dot_map: self.dot_map,
+            strict: self.strict,
+            origin: self.origin,
+            ..Canvas::new()
+        };
+    }
+
+    /// Sets every point of the canvas.
+    /// Only the lowest 8 bit of every character are changed.
+    /// Characters at the right and bottom edge that stick out of the canvas
+    /// only get the points inside of it.
+    pub fn set_all(&mut self) {
+        for i in 0..self.data.len() {
+            self.data[i] |= self.cell_mask(i % self.char_x, i / self.char_x);
+        }
+    }
+
+    /// Sets the point at a given x, y position. 0, 0 is top left unless set otherwise by `with_origin()`.
+    /// If coordinates are out of range returns an `IndexError` otherwise `OK()`.
+    #[allow(clippy::needless_return)]
+    pub fn set(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
+        let oy = self.origin_y(y);
+
+        return if let Some(i) = self.coords_to_index(x, oy) {
+            self.data[i] |= self.dot_map[(oy % 4) + (x % 2) * 4];
+
+            Ok(())
+        } else {
+            Err(IndexError::USizeMatrix(self.x, self.y, x, y))
+        };
+    }
+
+    /// Returns the braille character at the given character position `cx`, `cy`. 0, 0 is top left.
+    /// Returns `None` if out of range.
+    pub fn char_at(&self, cx: usize, cy: usize) -> Option<char> {
+        if cx < self.char_x && cy < self.char_y {
+            Some(data_to_char(self.data[cx + cy * self.char_x]))
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over every character as `(cx, cy, char)`, row by row,
+    /// where `cx` and `cy` are the character position. 0, 0 is top left.
+    /// Blank characters are included, so positions always line up with the grid.
+    pub fn cell_chars(&self) -> impl Iterator<Item = (usize, usize, char)> + '_ {
+        self.data
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| (i % self.char_x, i / self.char_x, data_to_char(value)))
+    }
+
+    /// Sets whether the clamping drawing methods, like `set_normalized_clamped()`,
+    /// panic when they have to clamp. This only takes effect in debug builds,
+    /// release builds always clamp silently.
+    /// Useful to catch drawing bugs during development. Off by default.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Sets the point at the normalized position `fx`, `fy`, where 0.0 is the first point and 1.0
+    /// the last point of each axis. 0.0, 0.0 is top left.
+    /// If a coordinate is outside of 0.0 to 1.0 returns an `IndexError` otherwise `OK()`.
+    /// Use `set_normalized_clamped()` to clamp instead.
+    pub fn set_normalized(&mut self, fx: f64, fy: f64) -> Result<(), IndexError> {
+        if !((0.0..=1.0).contains(&fx) && (0.0..=1.0).contains(&fy)) {
+            return Err(IndexError::InvalidArgument("normalized coordinates must be within 0.0 and 1.0"));
+        }
+
+        self.set_normalized_clamped(fx, fy)
+    }
+
+    /// Sets the point at the normalized position `fx`, `fy` like `set_normalized()`,
+    /// but clamps coordinates outside of 0.0 to 1.0 to the edge of the canvas.
+    /// In strict mode clamping panics in debug builds, see `set_strict()`.
+    /// If a coordinate is NaN or the canvas is empty returns an `IndexError` otherwise `OK()`.
+    pub fn set_normalized_clamped(&mut self, fx: f64, fy: f64) -> Result<(), IndexError> {
+        if fx.is_nan() || fy.is_nan() {
+            return Err(IndexError::InvalidArgument("normalized coordinates must not be NaN"));
+        }
+
+        debug_assert!(
+            !self.strict || ((0.0..=1.0).contains(&fx) && (0.0..=1.0).contains(&fy)),
+            "set_normalized_clamped clipped ({fx}, {fy}) in strict mode"
+        );
+
+        let scale = |f: f64, size: usize| (f.clamp(0.0, 1.0) * size.saturating_sub(1) as f64).round() as usize;
+
+        self.set(scale(fx, self.x), scale(fy, self.y))
+    }
+
+    /// Sets the point at a given x, y position like `set()`, but takes and returns the canvas,
+    /// so calls can be chained: `Canvas::with_size(10, 10).try_set(0, 0)?.try_set(1, 1)?`.
+    /// If coordinates are out of range returns an `IndexError` otherwise the canvas.
+    pub fn try_set(mut self, x: usize, y: usize) -> Result<Canvas, IndexError> {
+        self.set(x, y)?;
+
+        Ok(self)
+    }
+
+    /// Draws a grid of lines every `spacing_x` points horizontally and every
+    /// `spacing_y` points vertically, starting with lines at x=0 and y=0.
+    /// The grid always aligns to the top left, regardless of the origin set by `with_origin()`.
+    /// If a spacing is 0 returns an `IndexError` otherwise `OK()`.
+    pub fn grid(&mut self, spacing_x: usize, spacing_y: usize) -> Result<(), IndexError> {
+        if spacing_x == 0 || spacing_y == 0 {
+            let err = IndexError::InvalidArgument("grid spacing must not be 0");
+            return Err(err.in_operation("grid"));
+        }
+
+        for y in 0..self.y {
+            for x in 0..self.x {
+                if x % spacing_x == 0 || y % spacing_y == 0 {
+                    self.set_top_left(x, y);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the points of the character at `cx`, `cy` with the amount of points matching the
+    /// brightness `level`, see `BrailleChar::from_brightness()` for the fill order.
+    /// Points of the pattern that lie outside of the canvas are left out.
+    /// If the character position is out of range returns an `IndexError` otherwise `OK()`.
+    pub fn set_intensity(&mut self, cx: usize, cy: usize, level: f32) -> Result<(), IndexError> {
+        if cx >= self.char_x || cy >= self.char_y {
+            return Err(IndexError::USizeMatrix(self.char_x, self.char_y, cx, cy));
+        }
+
+        let value = self.glyph_to_data(BrailleChar::from_brightness(level)) & self.cell_mask(cx, cy);
+        let cell = &mut self.data[cx + cy * self.char_x];
+        *cell = (*cell & !0b11111111) | value;
+
+        Ok(())
+    }
+
+    /// Fills the canvas with a gradient of point density going from `from_intensity` to `to_intensity`,
+    /// top to bottom if `vertical` is true and left to right otherwise.
+    /// Intensities are clamped to 0.0 to 1.0 and existing points are replaced.
+    ///
+    /// Vertical gradients change per character row using `set_intensity()`.
+    /// Horizontal gradients change per column of points, filling each column from the bottom up,
+    /// so they get twice as many steps as there are characters.
+    /// Even so there are only a few distinct densities, so expect visible banding.
+    pub fn linear_gradient(&mut self, from_intensity: f32, to_intensity: f32, vertical: bool) {
+        let (from, to) = (from_intensity.clamp(0.0, 1.0), to_intensity.clamp(0.0, 1.0));
+        let lerp = |i: usize, steps: usize| {
+            if steps <= 1 {
+                from
+            } else {
+                from + (to - from) * i as f32 / (steps - 1) as f32
+            }
+        };
+
+        if vertical {
+            for cy in 0..self.char_y {
+                for cx in 0..self.char_x {
+                    self.set_intensity(cx, cy, lerp(cy, self.char_y)).unwrap();
+                    self.data[cx + cy * self.char_x] &= !0b11111111 | self.cell_mask(cx, cy);
+                }
+            }
+
+            return;
+        }
+
+        for (i, value) in self.data.iter_mut().enumerate() {
+            *value &= !0b11111111;
+
+            // Characters in the last row may stick out of the canvas, so only fill the rows inside.
+            let rows = min(4, self.y - (i / self.char_x) * 4);
+
+            for dx in 0..2 {
+                let x = (i % self.char_x) * 2 + dx;
+
+                if x >= self.x {
+                    continue;
+                }
+
+                let count = (lerp(x, self.x) * rows as f32).round() as usize;
+
+                for dy in rows - count..rows {
+                    *value |= self.dot_map[dy + dx * 4];
+                }
+            }
+        }
+    }
+
+    /// Sets every point with a probability of `density`, where 0.0 sets nothing and 1.0 everything.
+    /// Points that are already set stay set.
+    /// The same `seed` always produces the same pattern, which keeps snapshot tests stable.
+    pub fn noise(&mut self, density: f32, seed: u64) {
+        let mut state = seed;
+
+        for y in 0..self.y {
+            for x in 0..self.x {
+                let sample = (splitmix64(&mut state) >> 11) as f64 / (1u64 << 53) as f64;
+
+                if sample < density as f64 {
+                    self.set(x, y).unwrap();
+                }
+            }
+        }
+    }
+
+    /// Resets all points of the character at `cx`, `cy`, keeping its reserved upper bits.
+    /// If the character position is out of range returns an `IndexError` otherwise `OK()`.
+    pub fn clear_cell(&mut self, cx: usize, cy: usize) -> Result<(), IndexError> {
+        if cx >= self.char_x || cy >= self.char_y {
+            return Err(IndexError::USizeMatrix(self.char_x, self.char_y, cx, cy));
+        }
+
+        self.data[cx + cy * self.char_x] &= !0b11111111;
+
+        Ok(())
+    }
+
+    /// Flags the character at the character position `cx`, `cy` to blink or clears the flag,
+    /// see `to_ansi_string_with_blink()`. The points of the character stay untouched.
+    /// If `cx` or `cy` are out of range returns an `IndexError` otherwise `OK()`.
+    pub fn set_blink(&mut self, cx: usize, cy: usize, on: bool) -> Result<(), IndexError> {
+        if cx >= self.char_x || cy >= self.char_y {
+            return Err(IndexError::USizeMatrix(self.char_x, self.char_y, cx, cy));
+        }
+
+        let i = cy * self.char_x + cx;
+
+        if on {
+            self.blink.insert(i);
+        } else {
+            self.blink.remove(&i);
+        }
+
+        Ok(())
+    }
+
+    /// Adds the dots of `ch` to every character of the character row `cy`.
+    /// Dots that lie outside of the canvas are left out.
+    /// If `ch` is flagged to blink, the characters get flagged as well.
+    /// If `cy` is out of range returns an `IndexError` otherwise `OK()`.
+    pub fn fill_row_with(&mut self, cy: usize, ch: BrailleChar) -> Result<(), IndexError> {
+        if cy >= self.char_y {
+            return Err(IndexError::USizeMatrix(self.char_x, self.char_y, 0, cy));
+        }
+
+        let value = self.glyph_to_data(ch);
+
+        for cx in 0..self.char_x {
+            self.data[cy * self.char_x + cx] |= value & self.cell_mask(cx, cy);
+        }
+
+        if ch.is_blink() {
+            self.blink.extend(cy * self.char_x..(cy + 1) * self.char_x);
+        }
+
+        Ok(())
+    }
+
+    /// Adds the dots of `ch` to every character of the character column `cx`.
+    /// Dots that lie outside of the canvas are left out.
+    /// If `ch` is flagged to blink, the characters get flagged as well.
+    /// If `cx` is out of range returns an `IndexError` otherwise `OK()`.
+    pub fn fill_col_with(&mut self, cx: usize, ch: BrailleChar) -> Result<(), IndexError> {
+        if cx >= self.char_x {
+            return Err(IndexError::USizeMatrix(self.char_x, self.char_y, cx, 0));
+        }
+
+        let value = self.glyph_to_data(ch);
+
+        for cy in 0..self.char_y {
+            self.data[cy * self.char_x + cx] |= value & self.cell_mask(cx, cy);
+        }
+
+        if ch.is_blink() {
+            self.blink.extend((cx..self.data.len()).step_by(self.char_x));
+        }
+
+        Ok(())
+    }
+
+    /// Returns a view into the region of size `w` by `h` with its top left point at `x`, `y`.
+    /// Coordinates passed to the view are relative to the region and writes outside of it fail,
+    /// so drawing code can work in its own local coordinates.
+    /// The region gets clipped to the canvas.
+    pub fn view_mut(&mut self, x: usize, y: usize, w: usize, h: usize) -> CanvasView<'_> {
+        CanvasView::new(self, x, y, w, h)
+    }
+
+    /// Places the given icon with its top left point at the given x, y position. 0, 0 is top left.
+    /// The icon is combined with the existing points, so nothing gets reset.
+    /// If the icon doesn't fit on the canvas returns an `IndexError` otherwise `OK()`.
+    pub fn stamp_icon(&mut self, x: usize, y: usize, icon: Icon) -> Result<(), IndexError> {
+        self.stamp_glyphs(x, y, icon.glyphs())
+    }
+
+    /// Places `glyphs` next to each other with the top left point at x, y, see `stamp_icon()`.
+    /// No glyphs leave the canvas untouched.
+    fn stamp_glyphs(&mut self, x: usize, y: usize, glyphs: &[BrailleChar]) -> Result<(), IndexError> {
+        if glyphs.is_empty() {
+            return Ok(());
+        }
+
+        let last = x.checked_add(glyphs.len() * 2 - 1).zip(y.checked_add(3));
+        let Some((last_x, last_y)) = last else {
+            return Err(IndexError::USizeMatrix(self.x, self.y, x, y).in_operation("stamp_icon"));
+        };
+
+        self.check_point(last_x, last_y, "stamp_icon")?;
+
+        for (i, glyph) in glyphs.iter().enumerate() {
+            for dx in 0..2 {
+                for dy in 0..4 {
+                    if glyph.get(dx, dy)? {
+                        self.set(x + i * 2 + dx, self.origin_y(y + dy))?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the characters that changed compared to `previous` as `(cx, cy, char)`,
+    /// where `cx` and `cy` are the character position. 0, 0 is top left.
+    /// If both canvases differ in size every character is returned.
+    pub fn render_diff(&self, previous: &Canvas) -> Vec<(usize, usize, char)> {
+        let same_size = self.char_x == previous.char_x && self.char_y == previous.char_y;
+
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|&(i, value)| !same_size || (value ^ previous.data[i]) & 0b11111111 != 0)
+            .map(|(i, &value)| (i % self.char_x, i / self.char_x, data_to_char(value)))
+            .collect()
+    }
+
+    /// Writes the characters that changed compared to `previous` to `writer`,
+    /// moving the terminal cursor to each of them with ANSI escape codes.
+    /// Position 0, 0 of the canvas is drawn at the top left of the terminal.
+    pub fn write_diff<W: io::Write>(&self, previous: &Canvas, writer: &mut W) -> io::Result<()> {
+        for (cx, cy, c) in self.render_diff(previous) {
+            write!(writer, "\x1b[{};{}H{}", cy + 1, cx + 1, c)?;
+        }
+
+        writer.flush()
+    }
+
+    /// Renders the canvas into `w`, producing the same output as `to_string()`.
+    /// Useful to reuse a `String` or write into a custom sink without allocating.
+    pub fn render_fmt<W: std::fmt::Write>(&self, w: &mut W) -> std::fmt::Result {
+        for (i, &value) in self.data.iter().enumerate() {
+            if i != 0 && i % self.char_x == 0 {
+                w.write_char('\n')?;
+            }
+
+            w.write_char(data_to_char(value))?;
+        }
+
+        Ok(())
+    }
+
+    /// Renders the canvas like `to_string()` and pushes it to `frames`,
+    /// for example to record an animation with `frames_to_asciicast()`.
+    pub fn append_frame(&self, frames: &mut Vec<String>) {
+        frames.push(self.to_string());
+    }
+
+    /// Renders only the window of `cols` by `rows` characters starting at the character
+    /// position `cx`, `cy`, like `to_string()` would show it. 0, 0 is top left.
+    /// Parts of the window outside of the canvas are rendered as blank characters.
+    /// Useful to pan over a canvas larger than the terminal.
+    pub fn viewport_string(&self, cx: usize, cy: usize, cols: usize, rows: usize) -> String {
+        // Sized for the part of the window that can lie on the canvas, so huge windows can't overflow.
+        let mut string = String::with_capacity(cols.min(self.char_x) * rows.min(self.char_y) * 4);
+
+        for row in 0..rows {
+            if row != 0 {
+                string.push('\n');
+            }
+
+            for col in 0..cols {
+                let c = match (cx.checked_add(col), cy.checked_add(row)) {
+                    (Some(x), Some(y)) => self.char_at(x, y),
+                    _ => None,
+                };
+
+                string.push(c.unwrap_or('\u{2800}'));
+            }
+        }
+
+        string
+    }
+
+    /// Renders the canvas like `to_string()`, but prefixes every line with `indent` spaces.
+    pub fn to_string_indented(&self, indent: usize) -> String {
+        let prefix = " ".repeat(indent);
+
+        self.to_string()
+            .lines()
+            .map(|line| format!("{prefix}{line}"))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Renders the canvas like `to_string()` and adds `text` centered below it,
+    /// as wide as the canvas is in characters. `overflow` decides what happens to longer captions.
+    /// Lines are only padded on the left. A canvas without width gets the caption as it is.
+    pub fn with_caption(&self, text: &str, overflow: CaptionOverflow) -> String {
+        let width = self.char_x;
+        let mut lines: Vec<String> = Vec::new();
+
+        if width == 0 {
+            lines.push(text.to_string());
+        } else if overflow == CaptionOverflow::Truncate {
+            lines.push(text.chars().take(width).collect());
+        } else {
+            let mut line = String::new();
+
+            for word in text.split_whitespace() {
+                let mut word: Vec<char> = word.chars().collect();
+
+                if !line.is_empty() && line.chars().count() + 1 + word.len() > width {
+                    lines.push(std::mem::take(&mut line));
+                }
+
+                // Words longer than a whole line get split.
+                while word.len() > width {
+                    lines.push(word.drain(..width).collect());
+                }
+
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.extend(word);
+            }
+
+            if !line.is_empty() || lines.is_empty() {
+                lines.push(line);
+            }
+        }
+
+        let mut string = self.to_string();
+
+        for line in lines {
+            let padding = width.saturating_sub(line.chars().count()) / 2;
+            string.push('\n');
+            string.push_str(&" ".repeat(padding));
+            string.push_str(&line);
+        }
+
+        string
+    }
+
+    /// Renders the canvas like `to_string()`, but gives the character rows alternating
+    /// background colors using ANSI true color escape codes, starting with `even_bg` for row 0.
+    /// Colors are given as (red, green, blue). Every row ends with a reset code,
+    /// so the colors don't spill into the rest of the line.
+    pub fn to_ansi_zebra(&self, even_bg: (u8, u8, u8), odd_bg: (u8, u8, u8)) -> String {
+        let mut string = String::new();
+
+        for (cy, row) in self.data.chunks(self.char_x.max(1)).enumerate() {
+            if cy != 0 {
+                string.push('\n');
+            }
+
+            let (r, g, b) = if cy % 2 == 0 { even_bg } else { odd_bg };
+            string.push_str(&format!("\x1b[48;2;{r};{g};{b}m"));
+            string.extend(row.iter().map(|&value| data_to_char(value)));
+            string.push_str("\x1b[0m");
+        }
+
+        string
+    }
+
+    /// Renders the canvas like `to_string()`, but wraps every run of characters flagged to blink,
+    /// see `set_blink()`, in the ANSI blink escape codes. Characters without the flag render normally.
+    pub fn to_ansi_string_with_blink(&self) -> String {
+        let mut string = String::new();
+
+        for (cy, row) in self.data.chunks(self.char_x.max(1)).enumerate() {
+            if cy != 0 {
+                string.push('\n');
+            }
+
+            let mut blinking = false;
+
+            for (cx, &value) in row.iter().enumerate() {
+                let blink = self.blink.contains(&(cy * self.char_x + cx));
+
+                if blink && !blinking {
+                    string.push_str("\x1b[5m");
+                } else if !blink && blinking {
+                    string.push_str("\x1b[25m");
+                }
+
+                blinking = blink;
+                string.push(data_to_char(value));
+            }
+
+            if blinking {
+                string.push_str("\x1b[25m");
+            }
+        }
+
+        string
+    }
+
+    /// Renders the canvas like `to_string()`, but follows every character row with a line
+    /// holding the data of each character in hex, like `0xFF`. Only the lowest 8 bit are shown.
+    /// Meant for debugging, not for regular output.
+    pub fn to_string_annotated(&self) -> String {
+        let mut string = String::new();
+
+        for (cy, row) in self.data.chunks(self.char_x.max(1)).enumerate() {
+            if cy != 0 {
+                string.push('\n');
+            }
+
+            string.extend(row.iter().map(|&value| data_to_char(value)));
+            string.push('\n');
+
+            let bytes: Vec<String> = row.iter().map(|&value| format!("0x{:02X}", value & 0b11111111)).collect();
+            string.push_str(&bytes.join(" "));
+        }
+
+        string
+    }
+
+    /// Renders every character with `f` instead of the plain braille character,
+    /// putting a line break between character rows.
+    fn render_cells<F: Fn(u32) -> char>(&self, f: F) -> String {
+        let mut string = String::with_capacity(self.data.len() * 4);
+
+        for (i, &value) in self.data.iter().enumerate() {
+            if i != 0 && i % self.char_x == 0 {
+                string.push('\n');
+            }
+
+            string.push(f(value));
+        }
+
+        string
+    }
+
+    /// Renders the canvas like `to_string()`, but uses `blank` for every character without points.
+    /// Useful for contexts that collapse or mangle the blank braille character U+2800.
+    pub fn to_string_with_blank(&self, blank: char) -> String {
+        self.render_cells(|value| if value & 0b11111111 == 0 { blank } else { data_to_char(value) })
+    }
+
+    /// Renders the canvas like `to_string()`, but uses the full block character U+2588
+    /// for every character with all 8 points set, which many fonts draw denser and cleaner.
+    /// Partially set characters stay braille.
+    pub fn to_string_hybrid(&self) -> String {
+        self.render_cells(|value| if value & 0b11111111 == 0b11111111 { '█' } else { data_to_char(value) })
+    }
+
+    /// Returns a reader yielding the same UTF-8 bytes as `to_string()`,
+    /// without rendering the whole canvas into a string first.
+    /// Useful to `io::copy` a canvas into a file or socket.
+    pub fn reader(&self) -> impl io::Read + '_ {
+        CanvasReader {
+            canvas: self,
+            next_cell: 0,
+            buffer: [0; 8],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// Renders the canvas as a HTML `<pre>` block.
+    ///
+    /// The upper 24 bit of a character are read as an `0xRRGGBB` color.
+    /// Characters with color bits present get wrapped in a `<span>` setting that color,
+    /// characters without are left as they are.
+    pub fn to_html(&self) -> String {
+        let mut html = String::from("<pre>");
+
+        for (i, &value) in self.data.iter().enumerate() {
+            if i != 0 && i % self.char_x == 0 {
+                html.push('\n');
+            }
+
+            let color = value >> 8;
+
+            if color != 0 {
+                html.push_str(&format!("<span style=\"color:#{:06x}\">{}</span>", color, data_to_char(value)));
+            } else {
+                html.push(data_to_char(value));
+            }
+        }
+
+        html.push_str("</pre>");
+        html
+    }
+
+    /// Fills the triangle spanned by the points `a`, `b` and `c`. Points are given as (x, y).
+    /// If all three points lie on a line, the line between the two points furthest apart gets drawn.
+    /// If one of the points is out of range returns an `IndexError` otherwise `OK()`.
+    pub fn fill_triangle(
+        &mut self,
+        a: (usize, usize),
+        b: (usize, usize),
+        c: (usize, usize),
+    ) -> Result<(), IndexError> {
+        for (x, y) in [a, b, c] {
+            self.check_point(x, y, "fill_triangle")?;
+        }
+
+        let [a, b, c] = [a, b, c].map(|(x, y)| (x as isize, y as isize));
+        let edge = |p: (isize, isize), q: (isize, isize), r: (isize, isize)| {
+            (q.0 - p.0) * (r.1 - p.1) - (q.1 - p.1) * (r.0 - p.0)
+        };
+
+        if edge(a, b, c) == 0 {
+            let distance = |p: (isize, isize), q: (isize, isize)| (p.0 - q.0).pow(2) + (p.1 - q.1).pow(2);
+            let (p, q) = [(a, b), (b, c), (a, c)]
+                .into_iter()
+                .max_by_key(|&(p, q)| distance(p, q))
+                .unwrap();
+
+            for (x, y) in line_points(p.0, p.1, q.0, q.1) {
+                self.set(x as usize, y as usize)?;
+            }
+
+            return Ok(());
+        }
+
+        let (min_x, max_x) = (a.0.min(b.0).min(c.0), a.0.max(b.0).max(c.0));
+        let (min_y, max_y) = (a.1.min(b.1).min(c.1), a.1.max(b.1).max(c.1));
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let w = [edge(a, b, (x, y)), edge(b, c, (x, y)), edge(c, a, (x, y))];
+
+                if w.iter().all(|&v| v >= 0) || w.iter().all(|&v| v <= 0) {
+                    self.set(x as usize, y as usize)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Follows a path starting at `start` and moving by each of the relative `moves` in turn,
+    /// setting the point at every position visited, including `start`.
+    /// If `lines` is `true` consecutive positions get connected by lines as well.
+    /// If `start` is out of range or a move leads off the canvas returns an `IndexError`,
+    /// naming the index of the first failing move, otherwise `OK()`.
+    /// Nothing is drawn if the path fails.
+    pub fn set_path(
+        &mut self,
+        start: (usize, usize),
+        moves: &[(isize, isize)],
+        lines: bool,
+    ) -> Result<(), IndexError> {
+        self.check_point(start.0, start.1, "set_path")?;
+
+        let mut positions = Vec::with_capacity(moves.len() + 1);
+        positions.push(start);
+
+        for (step, &(dx, dy)) in moves.iter().enumerate() {
+            let (x, y) = positions[positions.len() - 1];
+            let err = |source: IndexError| IndexError::PathStep {
+                step,
+                source: Box::new(source),
+            };
+
+            let (Some(x), Some(y)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+                return Err(err(IndexError::InvalidArgument("path moved to a negative coordinate")));
+            };
+
+            if x >= self.x || y >= self.y {
+                return Err(err(IndexError::USizeMatrix(self.x, self.y, x, y)));
+            }
+
+            positions.push((x, y));
+        }
+
+        for (i, &(x, y)) in positions.iter().enumerate() {
+            if lines && i > 0 {
+                let (x0, y0) = positions[i - 1];
+                self.line_clipped(x0 as isize, y0 as isize, x as isize, y as isize);
+            }
+
+            self.set(x, y)?;
+        }
+
+        Ok(())
+    }
+
+    /// Draws the outline of the convex hull around `points`. Points are given as (x, y).
+    /// With fewer than three distinct points, or if all of them lie on a line,
+    /// this draws a single point or the line between the two points furthest apart.
+    /// An empty slice draws nothing.
+    /// If one of the points is out of range returns an `IndexError` otherwise `OK()`.
+    pub fn convex_hull(&mut self, points: &[(usize, usize)]) -> Result<(), IndexError> {
+        for &(x, y) in points {
+            self.check_point(x, y, "convex_hull")?;
+        }
+
+        let points: Vec<(isize, isize)> = points.iter().map(|&(x, y)| (x as isize, y as isize)).collect();
+        let hull = hull_points(&points);
+
+        for (i, &(x0, y0)) in hull.iter().enumerate() {
+            let (x1, y1) = hull[(i + 1) % hull.len()];
+
+            for (x, y) in line_points(x0, y0, x1, y1) {
+                self.set(x as usize, y as usize)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draws a dashed line from `x0`, `y0` to `x1`, `y1`.
+    /// Along the line `on` points get set, then `off` points get skipped and so on.
+    /// The pattern is counted in points along the line, so diagonals keep the same rhythm.
+    /// If `off` is 0 the line is solid.
+    /// If one of the endpoints is out of range returns an `IndexError` otherwise `OK()`.
+    pub fn dashed_line(
+        &mut self,
+        x0: usize,
+        y0: usize,
+        x1: usize,
+        y1: usize,
+        on: usize,
+        off: usize,
+    ) -> Result<(), IndexError> {
+        for (x, y) in [(x0, y0), (x1, y1)] {
+            self.check_point(x, y, "dashed_line")?;
+        }
+
+        let points = line_points(x0 as isize, y0 as isize, x1 as isize, y1 as isize);
+        let period = on.saturating_add(off);
+
+        for (i, (x, y)) in points.into_iter().enumerate() {
+            if off == 0 || i % period < on {
+                self.set(x as usize, y as usize)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Compares the rendered canvas with the `expected` multi-line braille string.
+    /// On a mismatch returns an error message naming the line and column (both starting at 1)
+    /// of the first character that differs, which helps when testing rendered output.
+    pub fn assert_matches(&self, expected: &str) -> Result<(), String> {
+        let rendered = self.to_string();
+        let mut actual_lines = rendered.lines();
+        let mut expected_lines = expected.lines();
+
+        for line in 1.. {
+            let (actual, expected) = match (actual_lines.next(), expected_lines.next()) {
+                (None, None) => return Ok(()),
+                (actual, expected) => (actual.unwrap_or(""), expected.unwrap_or("")),
+            };
+
+            let mut actual_chars = actual.chars();
+            let mut expected_chars = expected.chars();
+
+            for column in 1.. {
+                match (actual_chars.next(), expected_chars.next()) {
+                    (None, None) => break,
+                    (a, e) if a == e => continue,
+                    (a, e) => {
+                        let show = |c: Option<char>| c.map_or("end of line".to_string(), |c| format!("'{c}'"));
+
+                        return Err(format!(
+                            "mismatch at line {line}, column {column}: expected {} but got {}\n\
+                            expected:\n{expected}\n\
+                            got:\n{actual}",
+                            show(e),
+                            show(a),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets every point on the line from `x0`, `y0` to `x1`, `y1` that lies on the canvas.
+    /// The line gets clipped to the canvas first, so far away endpoints don't cost anything.
+    fn line_clipped(&mut self, x0: isize, y0: isize, x1: isize, y1: isize) {
+        if self.x == 0 || self.y == 0 {
+            return;
+        }
+
+        let max = (self.x as isize - 1, self.y as isize - 1);
+        let Some((start, end)) = clip_line((x0, y0), (x1, y1), max.0, max.1) else {
+            return;
+        };
+
+        for (x, y) in line_points(start.0, start.1, end.0, end.1) {
+            let _ = self.set(x as usize, y as usize);
+        }
+    }
+
+    /// Draws an Archimedean spiral starting at `cx`, `cy` and winding outwards clockwise
+    /// for `turns` full turns, with the distance between two turns being `spacing` points.
+    /// Parts of the spiral leaving the canvas are clipped.
+    /// If the center is out of range or `turns` or `spacing` are negative or not finite
+    /// returns an `IndexError` otherwise `OK()`.
+    pub fn spiral(&mut self, cx: usize, cy: usize, turns: f64, spacing: f64) -> Result<(), IndexError> {
+        self.check_point(cx, cy, "spiral")?;
+
+        if !(turns.is_finite() && turns >= 0.0 && spacing.is_finite() && spacing >= 0.0) {
+            let err = IndexError::InvalidArgument("turns and spacing must be finite and not negative");
+            return Err(err.in_operation("spiral"));
+        }
+
+        // Past the farthest corner no point lands on the canvas anymore, so the spiral can stop there.
+        let reach = (cx.max(self.x - 1 - cx) as f64).hypot(cy.max(self.y - 1 - cy) as f64);
+        let end = if spacing == 0.0 {
+            0.0
+        } else {
+            (turns * std::f64::consts::TAU).min((reach + 1.0) * std::f64::consts::TAU / spacing)
+        };
+        let point = |theta: f64| {
+            let r = spacing * theta / std::f64::consts::TAU;
+            (
+                (cx as f64 + r * theta.cos()).round() as isize,
+                (cy as f64 + r * theta.sin()).round() as isize,
+            )
+        };
+
+        let mut theta = 0.0;
+        let mut last = point(theta);
+        self.set(cx, cy)?;
+
+        while theta < end {
+            // Keep the distance between two samples at around one point.
+            let r = spacing * theta / std::f64::consts::TAU;
+            theta = (theta + (1.0 / r.max(1.0)).min(0.5)).min(end);
+
+            let next = point(theta);
+            self.line_clipped(last.0, last.1, next.0, next.1);
+            last = next;
+        }
+
+        Ok(())
+    }
+
+    /// Fills the ring around `cx`, `cy` by setting every point whose distance from the center
+    /// lies between `inner_r` and `outer_r`, both inclusive. An `inner_r` of 0 fills a whole circle.
+    /// Parts of the ring leaving the canvas are clipped.
+    /// If the center is out of range or `inner_r` is larger than `outer_r`
+    /// returns an `IndexError` otherwise `OK()`.
+    pub fn ring(&mut self, cx: usize, cy: usize, inner_r: usize, outer_r: usize) -> Result<(), IndexError> {
+        self.check_point(cx, cy, "ring")?;
+
+        if inner_r > outer_r {
+            let err = IndexError::InvalidArgument("inner radius must not be larger than outer radius");
+            return Err(err.in_operation("ring"));
+        }
+
+        let (inner, outer) = (inner_r.saturating_mul(inner_r), outer_r.saturating_mul(outer_r));
+
+        for y in cy.saturating_sub(outer_r)..=cy.saturating_add(outer_r).min(self.y - 1) {
+            for x in cx.saturating_sub(outer_r)..=cx.saturating_add(outer_r).min(self.x - 1) {
+                let distance = x.abs_diff(cx).pow(2) + y.abs_diff(cy).pow(2);
+
+                if (inner..=outer).contains(&distance) {
+                    self.set(x, y)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draws a cubic Bézier curve from `p0` to `p3` with the control points `p1` and `p2`.
+    /// Points are given as (x, y).
+    /// The curve gets sampled at `steps` evenly spaced points connected by lines.
+    /// If `steps` is 0 the curve gets subdivided until it is smooth.
+    /// The end points are always hit exactly, parts of the curve leaving the canvas are clipped.
+    /// If one of the end points is out of range returns an `IndexError` otherwise `OK()`.
+    pub fn cubic_bezier(
+        &mut self,
+        p0: (usize, usize),
+        p1: (usize, usize),
+        p2: (usize, usize),
+        p3: (usize, usize),
+        steps: usize,
+    ) -> Result<(), IndexError> {
+        for (x, y) in [p0, p3] {
+            self.check_point(x, y, "cubic_bezier")?;
+        }
+
+        let p = [p0, p1, p2, p3].map(|(x, y)| (x as f64, y as f64));
+        let mut points = vec![p[0]];
+
+        if steps == 0 {
+            subdivide_cubic(p, 16, &mut points);
+        } else {
+            points.extend((1..steps).map(|i| {
+                let t = i as f64 / steps as f64;
+                let u = 1.0 - t;
+                let [a, b, c, d] = [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+
+                (
+                    a * p[0].0 + b * p[1].0 + c * p[2].0 + d * p[3].0,
+                    a * p[0].1 + b * p[1].1 + c * p[2].1 + d * p[3].1,
+                )
+            }));
+            points.push(p[3]);
+        }
+
+        let points: Vec<(isize, isize)> = points
+            .into_iter()
+            .map(|(x, y)| (x.round() as isize, y.round() as isize))
+            .collect();
+
+        for pair in points.windows(2) {
+            self.line_clipped(pair[0].0, pair[0].1, pair[1].0, pair[1].1);
+        }
+
+        Ok(())
+    }
+
+    /// Draws an antialiased line from `x0`, `y0` to `x1`, `y1` using Xiaolin Wu's algorithm.
+    /// If one of the endpoints is out of range returns an `IndexError` otherwise `OK()`.
+    ///
+    /// Braille dots are either on or off, so the coverage of a dot can't be shaded.
+    /// Instead every dot the line covers by more than a third gets set,
+    /// meaning where the line passes between two dots both of them are lit.
+    /// At this resolution that reads as a slightly thicker line with softer steps
+    /// rather than real antialiasing, and it works best on shallow or steep lines.
+    pub fn line_aa(&mut self, x0: usize, y0: usize, x1: usize, y1: usize) -> Result<(), IndexError> {
+        for (x, y) in [(x0, y0), (x1, y1)] {
+            self.check_point(x, y, "line_aa")?;
+        }
+
+        // Walk along the major axis, so the minor axis changes by at most one dot per step.
+        let steep = y0.abs_diff(y1) > x0.abs_diff(x1);
+        let (mut a0, mut b0, mut a1, mut b1) = if steep {
+            (y0, x0, y1, x1)
+        } else {
+            (x0, y0, x1, y1)
+        };
+
+        if a0 > a1 {
+            std::mem::swap(&mut a0, &mut a1);
+            std::mem::swap(&mut b0, &mut b1);
+        }
+
+        let length = a1 - a0;
+        let gradient = if length == 0 {
+            0.0
+        } else {
+            (b1 as f64 - b0 as f64) / length as f64
+        };
+
+        for step in 0..=length {
+            let intersect = b0 as f64 + gradient * step as f64;
+            let lower = intersect.floor() as usize;
+            let coverage = intersect - intersect.floor();
+
+            let mut plot = |a: usize, b: usize| if steep { self.set(b, a) } else { self.set(a, b) };
+
+            if coverage <= 2.0 / 3.0 {
+                plot(a0 + step, lower)?;
+            }
+            if coverage > 1.0 / 3.0 {
+                plot(a0 + step, lower + 1)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Serializes the canvas into a compact binary format.
+    ///
+    /// The format starts with a header made up of the magic bytes `BRLC`, a version byte,
+    /// the width and height in points as little endian `u64`, a flags byte for the origin
+    /// and strict mode and the 8 bytes of the dot table.
+    /// After that follows one byte per character in row-major order.
+    /// Only the lowest 8 bit of every character are stored.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(BYTES_HEADER_LEN + self.data.len());
+        let mut flags = 0;
+
+        if self.origin == Origin::BottomLeft {
+            flags |= BYTES_FLAG_BOTTOM_LEFT;
+        }
+        if self.strict {
+            flags |= BYTES_FLAG_STRICT;
+        }
+
+        bytes.extend_from_slice(&BYTES_MAGIC);
+        bytes.push(BYTES_VERSION);
+        bytes.extend_from_slice(&(self.x as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.y as u64).to_le_bytes());
+        bytes.push(flags);
+        bytes.extend(self.dot_map.iter().map(|&bit| bit as u8));
+        bytes.extend(self.data.iter().map(|&value| value as u8));
+
+        bytes
+    }
+
+    /// Deserializes a canvas created by `to_bytes()`.
+    /// If the bytes are not a valid serialized canvas returns an `IndexError`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Canvas, IndexError> {
+        if bytes.len() < BYTES_HEADER_LEN || bytes[0..4] != BYTES_MAGIC {
+            return Err(IndexError::InvalidFormat("missing canvas header"));
+        }
+
+        if bytes[4] != BYTES_VERSION {
+            return Err(IndexError::InvalidFormat("unsupported canvas version"));
+        }
+
+        let x = usize::try_from(u64::from_le_bytes(bytes[5..13].try_into().unwrap()))
+            .map_err(|_| IndexError::InvalidFormat("width too large"))?;
+        let y = usize::try_from(u64::from_le_bytes(bytes[13..21].try_into().unwrap()))
+            .map_err(|_| IndexError::InvalidFormat("height too large"))?;
+
+        let cells = &bytes[BYTES_HEADER_LEN..];
+        let char_x = x / 2 + min(x % 2, 1);
+        let char_y = y / 4 + min(y % 4, 1);
+
+        if char_x.checked_mul(char_y) != Some(cells.len()) {
+            return Err(IndexError::InvalidFormat("cell count does not match dimensions"));
+        }
+
+        let (flags, map) = (bytes[21], &bytes[22..30]);
+        let map: [u32; 8] = std::array::from_fn(|i| map[i] as u32);
+
+        let mut canvas = Canvas::with_dot_map(x, y, map)
+            .map_err(|_| IndexError::InvalidFormat("invalid dot table"))?;
+        if flags & BYTES_FLAG_BOTTOM_LEFT != 0 {
+            canvas.origin = Origin::BottomLeft;
+        }
+        canvas.strict = flags & BYTES_FLAG_STRICT != 0;
+        canvas.data = cells.iter().map(|&v| v as u32).collect();
+
+        Ok(canvas)
+    }
+
+    /// Encodes the points in the run length encoded format used for Game of Life patterns.
+    /// The first line is the header `x = <width>, y = <height>`, followed by the rows of points,
+    /// where `o` is a set point, `b` is a point that isn't set and `$` ends a row.
+    /// Runs of the same symbol are prefixed with their length and the pattern ends with `!`.
+    /// 0, 0 is top left. Only the points are encoded, the reserved upper bits are not.
+    pub fn to_rle(&self) -> String {
+        let mut rle = format!("x = {}, y = {}\n", self.x, self.y);
+        let push_run = |rle: &mut String, count: usize, symbol: char| {
+            if count > 1 {
+                rle.push_str(&count.to_string());
+            }
+            if count > 0 {
+                rle.push(symbol);
+            }
+        };
+
+        let mut row_ends = 0;
+
+        for y in 0..self.y {
+            let mut runs: Vec<(usize, char)> = Vec::new();
+
+            for x in 0..self.x {
+                let symbol = if self.is_set_top_left(x, y) { 'o' } else { 'b' };
+
+                match runs.last_mut() {
+                    Some((count, last)) if *last == symbol => *count += 1,
+                    _ => runs.push((1, symbol)),
+                }
+            }
+
+            // Points that aren't set at the end of a row are implied.
+            if runs.last().is_some_and(|&(_, symbol)| symbol == 'b') {
+                runs.pop();
+            }
+
+            if runs.is_empty() {
+                row_ends += 1;
+                continue;
+            }
+
+            if y != 0 {
+                push_run(&mut rle, row_ends, '$');
+            }
+
+            for (count, symbol) in runs {
+                push_run(&mut rle, count, symbol);
+            }
+
+            row_ends = 1;
+        }
+
+        rle.push('!');
+        rle
+    }
+
+    /// Creates a new Canvas from a run length encoded Game of Life pattern like `to_rle()` creates.
+    /// Lines starting with `#` are skipped and whitespace in the pattern is ignored,
+    /// as are further header fields like the rule.
+    /// If the pattern is malformed or doesn't fit the size in its header returns an `IndexError`.
+    pub fn from_rle(s: &str) -> Result<Canvas, IndexError> {
+        let mut lines = s.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+        let header = lines.next().ok_or(IndexError::InvalidFormat("missing rle header"))?;
+
+        let mut size = (None, None);
+        for field in header.split(',') {
+            let (key, value) = field.split_once('=').ok_or(IndexError::InvalidFormat("invalid rle header"))?;
+            let value = || value.trim().parse::<usize>().map_err(|_| IndexError::InvalidFormat("invalid rle size"));
+
+            match key.trim() {
+                "x" => size.0 = Some(value()?),
+                "y" => size.1 = Some(value()?),
+                _ => {}
+            }
+        }
+
+        let (Some(width), Some(height)) = size else {
+            return Err(IndexError::InvalidFormat("rle header is missing the size"));
+        };
+
+        let (char_x, char_y) = (width.div_ceil(2), height.div_ceil(4));
+        let cells = char_x.checked_mul(char_y).ok_or(IndexError::InvalidFormat("rle size too large"))?;
+
+        // The size comes straight from the input, so allocating it must not abort on failure.
+        let mut data = Vec::new();
+        data.try_reserve_exact(cells)
+            .map_err(|_| IndexError::InvalidFormat("rle size too large"))?;
+        data.resize(cells, 0);
+
+        let mut canvas = Canvas {
+            x: width,
+            y: height,
+            char_x,
+            char_y,
+            data,
+            ..Canvas::new()
+        };
+        let (mut x, mut y) = (0usize, 0usize);
+        let mut count: Option<usize> = None;
+
+        for c in lines.flat_map(str::chars).filter(|c| !c.is_whitespace()) {
+            if let Some(digit) = c.to_digit(10) {
+                count = count
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|count| count.checked_add(digit as usize));
+
+                if count.is_none() {
+                    return Err(IndexError::InvalidFormat("rle run too long"));
+                }
+                continue;
+            }
+
+            let run = count.take().unwrap_or(1);
+
+            match c {
+                'b' | 'o' => {
+                    let end = x.checked_add(run).filter(|&end| end <= width && y < height);
+                    let end = end.ok_or(IndexError::InvalidFormat("rle pattern larger than its size"))?;
+
+                    if c == 'o' {
+                        for x in x..end {
+                            canvas.set(x, y).unwrap();
+                        }
+                    }
+                    x = end;
+                }
+                '$' => {
+                    y = y.saturating_add(run);
+                    x = 0;
+                }
+                '!' => return Ok(canvas),
+                _ => return Err(IndexError::InvalidFormat("unexpected symbol in rle pattern")),
+            }
+        }
+
+        Err(IndexError::InvalidFormat("rle pattern is missing its end"))
+    }
+}
+
+
+/// Lazily encodes the rendered canvas as UTF-8, one character at a time.
+struct CanvasReader<'a> {
+    canvas: &'a Canvas,
+    next_cell: usize,
+    buffer: [u8; 8],
+    start: usize,
+    end: usize,
+}
+
+
+impl io::Read for CanvasReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut written = 0;
+
+        while written < buf.len() {
+            if self.start == self.end {
+                let Some(&value) = self.canvas.data.get(self.next_cell) else {
+                    break;
+                };
+
+                self.start = 0;
+                self.end = 0;
+
+                if self.next_cell != 0 && self.next_cell.is_multiple_of(self.canvas.char_x) {
+                    self.buffer[0] = b'\n';
+                    self.end = 1;
+                }
+
+                self.end += data_to_char(value).encode_utf8(&mut self.buffer[self.end..]).len();
+                self.next_cell += 1;
+            }
+
+            let amount = (self.end - self.start).min(buf.len() - written);
+            buf[written..written + amount].copy_from_slice(&self.buffer[self.start..self.start + amount]);
+            self.start += amount;
+            written += amount;
+        }
+
+        Ok(written)
+    }
+}
+
+
+impl Display for Canvas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.render_fmt(f)
+    }
+}
+
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn canvas_with_size() {
+        let c = Canvas::with_size(3, 6);
+        dbg!(c.to_string());
+
+        let c = Canvas::with_size(2, 4);
+        dbg!(c.to_string());
+
+        let c = Canvas::with_size(4, 4);
+        dbg!(c.to_string());
+
+        let c = Canvas::with_size(7, 10);
+        dbg!(c);
+    }
+
+    #[test]
+    fn canvas_set() {
+        let mut c = Canvas::with_size(7, 10);
+        println!("{c} {} {}", c.char_x, c.char_y);
+
+        c.set(0, 0).unwrap();
+        c.set(6, 9).unwrap();
+        c.set(6, 0).unwrap();
+        c.set(0, 9).unwrap();
+        println!("{c}");
+    }
+
+    #[test]
+    fn canvas_reset() {
+        let mut c = Canvas::with_size(7, 10);
+        println!("{c} {} {}", c.char_x, c.char_y);
+
+        c.set(0, 0).unwrap();
+        c.set(6, 9).unwrap();
+        c.set(6, 0).unwrap();
+        c.set(0, 9).unwrap();
+        println!("{c}");
+
+        c.reset(0, 0).unwrap();
+        println!("{c}");
+        c.reset(6, 9).unwrap();
+        println!("{c}");
+        c.reset(6, 0).unwrap();
+        println!("{c}");
+        c.reset(0, 9).unwrap();
+        println!("{c}");
+    }
+
+    #[test]
+    fn canvas_reset_all() {
+        let mut c = Canvas::with_size(7, 10);
+        println!("{c} {} {}", c.char_x, c.char_y);
+
+        c.set(0, 0).unwrap();
+        c.set(6, 9).unwrap();
+        c.set(6, 0).unwrap();
+        c.set(0, 9).unwrap();
+        println!("{c}");
+
+        c.reset_all();
+        println!("{c}");
+    }
+
+    #[test]
+    fn canvas_flip() {
+        let mut c = Canvas::with_size(7, 10);
+        println!("{c} {} {}", c.char_x, c.char_y);
+
+        c.flip(0, 0).unwrap();
+        c.flip(6, 9).unwrap();
+        c.flip(6, 0).unwrap();
+        c.flip(0, 9).unwrap();
+        println!("{c}");
+
+        c.flip(0, 0).unwrap();
+        c.flip(6, 9).unwrap();
+        c.flip(6, 0).unwrap();
+        c.flip(0, 9).unwrap();
+        println!("{c}");
+    }
+
+    #[test]
+    fn canvas_line_aa() {
+        let mut c = Canvas::with_size(4, 4);
+        c.line_aa(0, 0, 3, 0).unwrap();
+        assert_eq!(c.data, vec![0b00001001, 0b00001001]);
+
+        let mut c = Canvas::with_size(4, 4);
+        c.line_aa(3, 3, 0, 0).unwrap();
+        assert_eq!(c.data, vec![0b00010001, 0b10000100]);
+
+        let mut c = Canvas::with_size(7, 10);
+        c.line_aa(0, 0, 6, 9).unwrap();
+        println!("{c}");
+
+        assert!(c.line_aa(0, 0, 7, 0).is_err());
+    }
+
+    #[test]
+    fn canvas_bytes() {
+        let mut c = Canvas::with_size(7, 10);
+        c.set(0, 0).unwrap();
+        c.set(6, 9).unwrap();
+        c.set(3, 5).unwrap();
+
+        let bytes = c.to_bytes();
+        assert_eq!(bytes.len(), BYTES_HEADER_LEN + 12);
+        assert_eq!(Canvas::from_bytes(&bytes).unwrap(), c);
+
+        let mut custom = Canvas::with_dot_map(7, 10, [16, 1, 128, 2, 8, 64, 4, 32]).unwrap();
+        custom.origin = Origin::BottomLeft;
+        custom.set_strict(true);
+        custom.set(0, 0).unwrap();
+        custom.set(5, 2).unwrap();
+        assert_eq!(Canvas::from_bytes(&custom.to_bytes()).unwrap(), custom);
+
+        let mut bad_map = bytes.clone();
+        bad_map[22] = 0;
+        assert!(Canvas::from_bytes(&bad_map).is_err());
+
+        assert_eq!(Canvas::from_bytes(&Canvas::new().to_bytes()).unwrap(), Canvas::new());
+
+        assert!(Canvas::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Canvas::from_bytes(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn canvas_char_at() {
+        let mut c = Canvas::with_size(7, 10);
+        c.set(0, 0).unwrap();
+        c.set(6, 9).unwrap();
+
+        assert_eq!(c.char_at(0, 0), Some('⠁'));
+        assert_eq!(c.char_at(3, 2), Some('⠂'));
+        assert_eq!(c.char_at(1, 1), Some('⠀'));
+        assert_eq!(c.char_at(4, 0), None);
+        assert_eq!(c.char_at(0, 3), None);
+    }
+
+    #[test]
+    fn canvas_stamp_icon() {
+        let mut c = Canvas::with_size(4, 4);
+        c.stamp_icon(0, 0, Icon::Heart).unwrap();
+        assert_eq!(c.to_string(), "⠳⠞");
+
+        let mut c = Canvas::with_size(5, 6);
+        c.stamp_icon(1, 1, Icon::Check).unwrap();
+        println!("{c}");
+
+        assert!(c.stamp_icon(2, 0, Icon::Check).is_err());
+        assert!(c.stamp_icon(4, 2, Icon::Spinner(0)).is_err());
+        c.stamp_icon(3, 2, Icon::Spinner(0)).unwrap();
+
+        assert!(c.stamp_icon(usize::MAX, 0, Icon::Heart).is_err());
+        assert!(c.stamp_icon(0, usize::MAX - 1, Icon::Heart).is_err());
+
+        let before = c.clone();
+        c.stamp_glyphs(0, 0, &[]).unwrap();
+        c.stamp_glyphs(usize::MAX, usize::MAX, &[]).unwrap();
+        assert_eq!(c, before);
+    }
+
+    #[test]
+    fn canvas_is_consistent() {
+        assert!(Canvas::new().is_consistent());
+        assert!(Canvas::with_size(7, 10).is_consistent());
+
+        let mut c = Canvas::with_size(7, 10);
+        c.data.pop();
+        assert!(!c.is_consistent());
+
+        let mut c = Canvas::with_size(7, 10);
+        c.x = 9;
+        assert!(!c.is_consistent());
+    }
+
+    #[test]
+    fn canvas_to_html() {
+        let mut c = Canvas::with_size(4, 8);
+        c.set(0, 0).unwrap();
+        assert_eq!(c.to_html(), "<pre>⠁⠀\n⠀⠀</pre>");
+
+        c.data[3] |= 0xff8000 << 8;
+        assert_eq!(c.to_html(), "<pre>⠁⠀\n⠀<span style=\"color:#ff8000\">⠀</span></pre>");
+
+        c.set_blink(1, 0, true).unwrap();
+        c.data[0] |= 0x0000ff << 8;
+        assert_eq!(
+            c.to_html(),
+            "<pre><span style=\"color:#0000ff\">⠁</span>⠀\n⠀<span style=\"color:#ff8000\">⠀</span></pre>"
+        );
+
+        assert_eq!(Canvas::new().to_html(), "<pre></pre>");
+    }
+
+    #[test]
+    fn canvas_fill_triangle() {
+        let count = |c: &Canvas| c.data.iter().map(|v| (v & 0b11111111).count_ones()).sum::<u32>();
+
+        let mut c = Canvas::with_size(7, 10);
+        c.fill_triangle((0, 0), (3, 0), (0, 3)).unwrap();
+        assert_eq!(count(&c), 10);
+        println!("{c}");
+
+        let mut c = Canvas::with_size(7, 10);
+        c.fill_triangle((0, 0), (6, 9), (0, 9)).unwrap();
+        println!("{c}");
+
+        let mut c = Canvas::with_size(7, 10);
+        c.fill_triangle((0, 0), (4, 4), (2, 2)).unwrap();
+        assert_eq!(count(&c), 5);
+
+        assert!(c.fill_triangle((0, 0), (7, 0), (0, 3)).is_err());
+    }
+
+    #[test]
+    fn canvas_with_dot_map() {
+        let mut c = Canvas::with_dot_map(2, 4, [1, 2, 4, 8, 16, 32, 64, 128]).unwrap();
+        c.set(0, 3).unwrap();
+        assert_eq!(c.to_string(), "⠈");
+
+        c.flip(1, 0).unwrap();
+        assert_eq!(c.to_string(), "⠘");
+
+        c.reset(0, 3).unwrap();
+        assert_eq!(c.to_string(), "⠐");
+
+        assert!(Canvas::with_dot_map(2, 4, [1, 1, 4, 8, 16, 32, 64, 128]).is_err());
+        assert!(Canvas::with_dot_map(2, 4, [0, 2, 4, 8, 16, 32, 64, 128]).is_err());
+        assert!(Canvas::with_dot_map(2, 4, [256, 2, 4, 8, 16, 32, 64, 128]).is_err());
+        assert!(Canvas::with_dot_map(2, 4, [3, 0, 4, 8, 16, 32, 64, 128]).is_err());
+    }
+
+    #[test]
+    fn canvas_set_all() {
+        let mut c = Canvas::with_size(4, 8);
+        c.data[1] |= 0xabcdef << 8;
+
+        c.set_all();
+        assert_eq!(c.to_string(), "⣿⣿\n⣿⣿");
+        assert_eq!(c.data[1] >> 8, 0xabcdef);
+
+        c.reset_all();
+        assert_eq!(c.to_string(), "⠀⠀\n⠀⠀");
+
+        let mut c = Canvas::with_size(3, 6);
+        c.set_all();
+        assert_eq!(c.to_string(), "⣿⡇\n⠛⠃");
+        assert!(c.is_consistent());
+        assert_eq!(c.bounds(), Some((0, 0, 2, 5)));
+        assert_eq!(c.set_points().count(), 18);
+    }
+
+    #[test]
+    fn canvas_for_terminal() {
+        let c = Canvas::for_terminal(3, 2);
+        assert_eq!((c.x, c.y, c.char_x, c.char_y), (6, 8, 3, 2));
+        assert_eq!(c, Canvas::with_size(6, 8));
+    }
+
+    #[test]
+    fn canvas_dashed_line() {
+        let mut c = Canvas::with_size(10, 4);
+        c.dashed_line(0, 0, 9, 0, 2, 1).unwrap();
+        assert_eq!(c.to_string(), "⠉⠈⠁⠉⠈");
+
+        let mut c = Canvas::with_size(10, 4);
+        c.dashed_line(9, 0, 0, 0, 3, 0).unwrap();
+        assert_eq!(c.to_string(), "⠉⠉⠉⠉⠉");
+
+        let mut c = Canvas::with_size(8, 8);
+        c.dashed_line(0, 0, 7, 7, 1, 1).unwrap();
+        println!("{c}");
+
+        assert!(c.dashed_line(0, 0, 0, 8, 1, 1).is_err());
+
+        let mut c = Canvas::with_size(10, 4);
+        c.dashed_line(0, 0, 9, 0, usize::MAX, 1).unwrap();
+        assert_eq!(c.to_string(), "⠉⠉⠉⠉⠉");
+        c.dashed_line(0, 3, 9, 3, 1, usize::MAX).unwrap();
+        assert_eq!(c.to_string(), "⡉⠉⠉⠉⠉");
+    }
+
+    #[test]
+    fn canvas_composite() {
+        let mut a = Canvas::with_size(4, 4);
+        a.set(0, 0).unwrap();
+        let mut b = Canvas::with_size(4, 4);
+        b.set(3, 3).unwrap();
+        let mut c = Canvas::with_size(4, 4);
+        c.set(0, 1).unwrap();
+
+        let combined = Canvas::composite(&[&a, &b, &c]).unwrap();
+        assert_eq!(combined.to_string(), "⠃⢀");
+
+        assert_eq!(Canvas::composite(&[]).unwrap(), Canvas::new());
+
+        let mut mapped = Canvas::with_dot_map(4, 4, [128, 64, 32, 16, 8, 4, 2, 1]).unwrap();
+        mapped.set(0, 0).unwrap();
+        mapped.set(3, 3).unwrap();
+        let combined = Canvas::composite(&[&c, &mapped]).unwrap();
+        assert_eq!(combined.to_string(), "⠃⢀");
+        let combined = Canvas::composite(&[&mapped, &c]).unwrap();
+        assert!(combined.get(0, 0).unwrap() && combined.get(0, 1).unwrap() && combined.get(3, 3).unwrap());
+        assert_eq!(combined.set_points().count(), 3);
+
+        let wrong = Canvas::with_size(4, 8);
+        assert!(matches!(
+            Canvas::composite(&[&a, &b, &wrong, &wrong]),
+            Err(IndexError::LayerMismatch(2, 4, 4, 4, 8))
+        ));
+    }
+
+    #[test]
+    fn canvas_get() {
+        let mut c = Canvas::with_size(7, 10);
+        c.set(5, 6).unwrap();
+
+        assert!(c.get(5, 6).unwrap());
+        assert!(!c.get(4, 6).unwrap());
+        assert!(c.get(0, 10).is_err());
+    }
+
+    #[test]
+    fn canvas_bounds() {
+        let mut c = Canvas::with_size(7, 10);
+        assert_eq!(c.bounds(), None);
+
+        c.set(3, 2).unwrap();
+        assert_eq!(c.bounds(), Some((3, 2, 3, 2)));
+
+        c.set(6, 9).unwrap();
+        c.set(1, 5).unwrap();
+        assert_eq!(c.bounds(), Some((1, 2, 6, 9)));
+    }
+
+    #[test]
+    fn canvas_downsample_2x() {
+        let mut c = Canvas::with_size(7, 10);
+        c.set(0, 0).unwrap();
+        c.set(1, 1).unwrap();
+        c.set(3, 2).unwrap();
+        c.set(6, 9).unwrap();
+
+        let small = c.downsample_2x();
+        assert_eq!((small.x, small.y), (4, 5));
+
+        let mut expected = Canvas::with_size(4, 5);
+        expected.set(0, 0).unwrap();
+        expected.set(1, 1).unwrap();
+        expected.set(3, 4).unwrap();
+        assert_eq!(small, expected);
+    }
+
+    #[test]
+    fn canvas_assert_matches() {
+        let mut c = Canvas::with_size(4, 8);
+        c.set(0, 0).unwrap();
+        c.set(3, 7).unwrap();
+
+        assert!(c.assert_matches("⠁⠀\n⠀⢀").is_ok());
+
+        let err = c.assert_matches("⠁⠀\n⠀⠀").unwrap_err();
+        assert!(err.starts_with("mismatch at line 2, column 2: expected '⠀' but got '⢀'"));
+
+        let err = c.assert_matches("⠁⠀").unwrap_err();
+        assert!(err.starts_with("mismatch at line 2, column 1: expected end of line but got '⠀'"));
+    }
+
+    #[test]
+    fn canvas_aspect_correct_y() {
+        assert_eq!(Canvas::aspect_correct_y(0), 0);
+        assert_eq!(Canvas::aspect_correct_y(10), 5);
+        assert_eq!(Canvas::aspect_correct_y(7), 4);
+    }
+
+    #[test]
+    fn canvas_try_set() {
+        let c = Canvas::with_size(4, 4).try_set(0, 0).and_then(|c| c.try_set(3, 3)).unwrap();
+        assert_eq!(c.to_string(), "⠁⢀");
+
+        assert!(Canvas::with_size(4, 4).try_set(0, 0).and_then(|c| c.try_set(4, 0)).is_err());
+    }
+
+    #[test]
+    fn canvas_clear() {
+        let mut c = Canvas::with_size(7, 10);
+        c.set(0, 0).unwrap();
+
+        c.clear();
+        assert_eq!(c, Canvas::new());
+        assert_eq!(c.data.capacity(), 0);
+        assert!(c.set(0, 0).is_err());
+    }
+
+    #[test]
+    fn canvas_grid() {
+        let mut c = Canvas::with_size(8, 8);
+        c.grid(4, 4).unwrap();
+        assert_eq!(c.to_string(), "⡏⠉⡏⠉\n⡏⠉⡏⠉");
+
+        assert!(c.grid(0, 2).is_err());
+        assert!(c.grid(2, 0).is_err());
+
+        let mut top = Canvas::with_size(8, 6);
+        top.grid(4, 4).unwrap();
+        let mut bottom = Canvas::with_origin(8, 6, Origin::BottomLeft);
+        bottom.grid(4, 4).unwrap();
+        assert_eq!(bottom.to_string(), top.to_string());
+        assert!(bottom.get(1, 5).unwrap());
+    }
+
+    #[test]
+    fn canvas_from_str_grid() {
+        let c = Canvas::from_str_grid("⣿⠁\n⡀", DotMode::default()).unwrap();
+        assert_eq!((c.x, c.y), (4, 8));
+        assert_eq!(c.to_string(), "⣿⠁\n⡀⠀");
+
+        let c = Canvas::from_str_grid("⣿⠁\n⡀", DotMode::Six).unwrap();
+        assert_eq!(c.to_string(), "⠿⠁\n⠀⠀");
+
+        assert!(Canvas::from_str_grid("⣿a", DotMode::Eight).is_err());
+        assert_eq!(Canvas::from_str_grid("", DotMode::Eight).unwrap(), Canvas::new());
+    }
+
+    #[test]
+    fn canvas_rotate_90() {
+        // L-shape: vertical bar on the left and a foot to the right at the bottom.
+        let mut c = Canvas::with_size(3, 5);
+        for y in 0..5 {
+            c.set(0, y).unwrap();
+        }
+        c.set(1, 4).unwrap();
+        c.set(2, 4).unwrap();
+
+        let cw = c.rotate_90_cw();
+        assert_eq!((cw.x, cw.y), (5, 3));
+        let mut expected = Canvas::with_size(5, 3);
+        for x in 0..5 {
+            expected.set(x, 0).unwrap();
+        }
+        expected.set(0, 1).unwrap();
+        expected.set(0, 2).unwrap();
+        assert_eq!(cw, expected);
+
+        assert_eq!(c.rotate_90_ccw(), cw.rotate_90_cw().rotate_90_cw());
+        assert_eq!(cw.rotate_90_ccw(), c);
+        assert_eq!(cw.rotate_90_cw().rotate_90_cw().rotate_90_cw(), c);
+    }
+
+    #[test]
+    fn canvas_render_diff() {
+        let previous = Canvas::with_size(7, 10);
+        let mut c = previous.clone();
+        assert!(c.render_diff(&previous).is_empty());
+
+        c.set(0, 0).unwrap();
+        c.set(6, 9).unwrap();
+        assert_eq!(c.render_diff(&previous), vec![(0, 0, '⠁'), (3, 2, '⠂')]);
+
+        let mut output = Vec::new();
+        c.write_diff(&previous, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "\x1b[1;1H⠁\x1b[3;4H⠂");
+
+        assert_eq!(c.render_diff(&Canvas::new()).len(), 12);
+    }
+
+    #[test]
+    fn canvas_with_capacity() {
+        let c = Canvas::with_capacity(7, 10, 20);
+        assert!(c.data.capacity() >= 32);
+        assert_eq!(c, Canvas::with_size(7, 10));
+    }
+
+    #[test]
+    fn canvas_cell_bytes() {
+        let mut c = Canvas::with_size(3, 6);
+        c.set(0, 0).unwrap();
+        c.set(2, 5).unwrap();
+        c.data[0] |= 0xffffff << 8;
+
+        assert_eq!((c.char_x(), c.char_y()), (2, 2));
+        assert_eq!(c.cell_bytes(), vec![0b00000001, 0, 0, 0b00000010]);
+    }
+
+    #[test]
+    fn canvas_error_operation() {
+        let mut c = Canvas::with_size(4, 4);
+        let err = c.line_aa(0, 0, 4, 1).unwrap_err();
+
+        assert_eq!(err.to_string(), "line_aa failed");
+        assert!(matches!(
+            err,
+            IndexError::InOperation { op: "line_aa", ref source }
+                if matches!(**source, IndexError::USizeMatrix(4, 4, 4, 1))
+        ));
+    }
+
+    #[test]
+    fn canvas_set_intensity() {
+        let mut c = Canvas::with_size(4, 4);
+        c.set_all();
+
+        c.set_intensity(1, 0, 0.5).unwrap();
+        assert_eq!(c.to_string(), "⣿⣤");
+
+        c.set_intensity(0, 0, 0.0).unwrap();
+        assert_eq!(c.to_string(), "⠀⣤");
+
+        assert!(c.set_intensity(2, 0, 1.0).is_err());
+
+        let mut c = Canvas::with_size(3, 6);
+        c.set_intensity(1, 1, 1.0).unwrap();
+        assert_eq!(c.to_string(), "⠀⠀\n⠀⠃");
+        assert_eq!(c.bounds(), Some((2, 4, 2, 5)));
+    }
+
+    #[test]
+    fn canvas_symmetric_difference() {
+        let mut a = Canvas::with_size(4, 4);
+        a.set(0, 0).unwrap();
+        a.set(1, 1).unwrap();
+        let mut b = Canvas::with_size(4, 4);
+        b.set(1, 1).unwrap();
+        b.set(3, 3).unwrap();
+
+        let diff = a.symmetric_difference(&b).unwrap();
+        assert_eq!(diff.to_string(), "⠁⢀");
+        assert_eq!(diff, b.symmetric_difference(&a).unwrap());
+
+        assert!(a.symmetric_difference(&Canvas::with_size(4, 5)).is_err());
+    }
+
+    #[test]
+    fn canvas_map_points() {
+        let mut c = Canvas::with_size(7, 10);
+        c.set(0, 0).unwrap();
+        c.set(2, 5).unwrap();
+
+        let mirrored = c.map_points(|x, y| Some((6 - x, y)));
+        let mut expected = Canvas::with_size(7, 10);
+        expected.set(6, 0).unwrap();
+        expected.set(4, 5).unwrap();
+        assert_eq!(mirrored, expected);
+
+        let dropped = c.map_points(|x, y| if x == 0 { None } else { Some((x + 10, y)) });
+        assert_eq!(dropped.bounds(), None);
+    }
+
+    #[test]
+    fn canvas_to_string_with_blank() {
+        let mut c = Canvas::with_size(4, 8);
+        c.set(0, 0).unwrap();
+        c.set(3, 7).unwrap();
+
+        assert_eq!(c.to_string_with_blank(' '), "⠁ \n ⢀");
+        assert_eq!(c.to_string_with_blank('⠀'), c.to_string());
+    }
+
+    #[test]
+    fn canvas_row_column_bits() {
+        let mut c = Canvas::with_size(3, 5);
+        c.set(0, 0).unwrap();
+        c.set(2, 0).unwrap();
+        c.set(2, 4).unwrap();
+
+        assert_eq!(c.row_bits(0).unwrap(), vec![true, false, true]);
+        assert_eq!(c.row_bits(4).unwrap(), vec![false, false, true]);
+        assert_eq!(c.column_bits(2).unwrap(), vec![true, false, false, false, true]);
+        assert_eq!(c.column_bits(1).unwrap(), vec![false; 5]);
+
+        assert!(c.row_bits(5).is_err());
+        assert!(c.column_bits(3).is_err());
+    }
+
+    #[test]
+    fn canvas_spiral() {
+        let mut c = Canvas::with_size(40, 40);
+        c.spiral(20, 20, 3.0, 4.0).unwrap();
+        println!("{c}");
+        assert!(c.get(20, 20).unwrap());
+        assert!(c.get(32, 20).unwrap());
+        assert_eq!(c.bounds(), Some((10, 9, 32, 29)));
+
+        let mut c = Canvas::with_size(10, 10);
+        c.spiral(0, 0, 5.0, 3.0).unwrap();
+        assert!(c.get(0, 0).unwrap());
+
+        let mut c = Canvas::with_size(10, 10);
+        c.spiral(5, 5, 0.0, 3.0).unwrap();
+        assert_eq!(c.bounds(), Some((5, 5, 5, 5)));
+
+        let mut c = Canvas::with_size(10, 10);
+        c.spiral(5, 5, 3.0, 1e16).unwrap();
+        assert!(c.get(5, 5).unwrap());
+        c.spiral(5, 5, 1e300, 1.0).unwrap();
+        c.spiral(5, 5, 1e300, 0.0).unwrap();
+        let mut full = Canvas::with_size(10, 10);
+        full.spiral(5, 5, 1e6, 0.5).unwrap();
+        assert!(full.get(0, 0).unwrap());
+
+        assert!(c.spiral(10, 0, 1.0, 1.0).is_err());
+        assert!(c.spiral(5, 5, -1.0, 1.0).is_err());
+        assert!(c.spiral(5, 5, 1.0, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn canvas_from_packed_bits() {
+        // 3x3 points: diagonal from the top left to the bottom right.
+        let c = Canvas::from_packed_bits(3, 3, &[0b10001000, 0b10000000]).unwrap();
+
+        let mut expected = Canvas::with_size(3, 3);
+        expected.set(0, 0).unwrap();
+        expected.set(1, 1).unwrap();
+        expected.set(2, 2).unwrap();
+        assert_eq!(c, expected);
+
+        assert!(Canvas::from_packed_bits(3, 3, &[0b10001000]).is_err());
+        assert!(Canvas::from_packed_bits(usize::MAX, 2, &[]).is_err());
+    }
+
+    /// Compares the bulk import with setting every point on its own.
+    /// Run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn canvas_from_packed_bits_timing() {
+        let (width, height): (usize, usize) = (1920, 1080);
+        let mut state = 42;
+        let bits: Vec<u8> = (0..(width * height).div_ceil(8)).map(|_| splitmix64(&mut state) as u8).collect();
+
+        let start = std::time::Instant::now();
+        let bulk = Canvas::from_packed_bits(width, height, &bits).unwrap();
+        let bulk_time = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let mut per_point = Canvas::with_size(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let i = x + y * width;
+
+                if bits[i / 8] & (0b10000000 >> (i % 8)) != 0 {
+                    per_point.set(x, y).unwrap();
+                }
+            }
+        }
+        let per_point_time = start.elapsed();
+
+        assert_eq!(bulk, per_point);
+        println!("from_packed_bits: {bulk_time:?}, per point set: {per_point_time:?}");
+    }
+
+    #[test]
+    fn canvas_reader() {
+        use std::io::Read;
+
+        let mut c = Canvas::with_size(7, 10);
+        c.set(0, 0).unwrap();
+        c.set(6, 9).unwrap();
+
+        let mut bytes = Vec::new();
+        c.reader().read_to_end(&mut bytes).unwrap();
+        assert_eq!(bytes, c.to_string().into_bytes());
+
+        // Reading in tiny chunks splits characters, which must still produce the same bytes.
+        let mut reader = c.reader();
+        let mut bytes = Vec::new();
+        let mut chunk = [0u8; 2];
+        loop {
+            match reader.read(&mut chunk).unwrap() {
+                0 => break,
+                n => bytes.extend_from_slice(&chunk[..n]),
+            }
+        }
+        assert_eq!(bytes, c.to_string().into_bytes());
+
+        let mut bytes = Vec::new();
+        Canvas::new().reader().read_to_end(&mut bytes).unwrap();
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn canvas_linear_gradient() {
+        let mut c = Canvas::with_size(10, 4);
+        c.linear_gradient(0.0, 1.0, false);
+        assert_eq!(c.to_string(), "⠀⣀⣤⣶⣿");
+
+        let mut c = Canvas::with_size(2, 12);
+        c.linear_gradient(1.0, 0.0, true);
+        assert_eq!(c.to_string(), "⣿\n⣤\n⠀");
+
+        c.linear_gradient(-1.0, -1.0, true);
+        assert_eq!(c.bounds(), None);
+
+        let mut c = Canvas::with_size(10, 2);
+        c.linear_gradient(0.0, 1.0, false);
+        assert_eq!(c.to_string(), "⠀⠐⠒⠚⠛");
+        assert_eq!(c.bounds(), Some((3, 0, 9, 1)));
+
+        let mut c = Canvas::with_size(10, 6);
+        c.linear_gradient(1.0, 1.0, false);
+        assert_eq!(c.to_string(), "⣿⣿⣿⣿⣿\n⠛⠛⠛⠛⠛");
+
+        let mut c = Canvas::with_size(4, 6);
+        c.linear_gradient(1.0, 1.0, true);
+        assert_eq!(c.to_string(), "⣿⣿\n⠛⠛");
+    }
+
+    #[test]
+    fn canvas_display_width() {
+        assert_eq!(Canvas::new().display_width(), 0);
+        assert_eq!(Canvas::with_size(7, 10).display_width(), 4);
+
+        let c = Canvas::with_size(7, 10);
+        assert!(c.to_string().lines().all(|line| line.chars().count() == c.display_width()));
+    }
+
+    #[test]
+    fn canvas_empty_rows_cols() {
+        let mut c = Canvas::with_size(7, 10);
+        assert_eq!(c.empty_rows(), vec![0, 1, 2]);
+        assert_eq!(c.empty_cols(), vec![0, 1, 2, 3]);
+
+        c.set(3, 5).unwrap();
+        assert_eq!(c.empty_rows(), vec![0, 2]);
+        assert_eq!(c.empty_cols(), vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn canvas_noise() {
+        let count = |c: &Canvas| c.set_points().count();
+
+        let mut a = Canvas::with_size(40, 40);
+        a.noise(0.3, 42);
+        let mut b = Canvas::with_size(40, 40);
+        b.noise(0.3, 42);
+        assert_eq!(a, b);
+        assert!((400..560).contains(&count(&a)));
+
+        b.reset_all();
+        b.noise(0.3, 43);
+        assert_ne!(a, b);
+
+        let mut c = Canvas::with_size(7, 10);
+        c.noise(0.0, 1);
+        assert_eq!(count(&c), 0);
+        c.noise(1.0, 1);
+        assert_eq!(count(&c), 70);
+    }
+
+    #[test]
+    fn canvas_fill_row_col_with() {
+        let mut c = Canvas::with_size(6, 8);
+        c.fill_row_with(1, BrailleChar::with_data(0b00001001)).unwrap();
+        assert_eq!(c.to_string(), "⠀⠀⠀\n⠉⠉⠉");
+
+        c.fill_col_with(2, BrailleChar::with_data(0b01000111)).unwrap();
+        assert_eq!(c.to_string(), "⠀⠀⡇\n⠉⠉⡏");
+
+        assert!(c.fill_row_with(2, BrailleChar::new()).is_err());
+        assert!(c.fill_col_with(3, BrailleChar::new()).is_err());
+
+        let mut c = Canvas::with_size(3, 6);
+        c.fill_row_with(1, BrailleChar::with_data(0b11111111)).unwrap();
+        assert_eq!(c.to_string(), "⠀⠀\n⠛⠃");
+        c.fill_col_with(1, BrailleChar::with_data(0b11111111)).unwrap();
+        assert_eq!(c.to_string(), "⠀⡇\n⠛⠃");
+        assert_eq!(c.bounds(), Some((0, 0, 2, 5)));
+        assert_eq!(c.cell_state(1, 1).unwrap(), CellState::Partial);
+    }
+
+    #[test]
+    fn canvas_cell_state() {
+        let mut c = Canvas::with_size(6, 4);
+        c.set(0, 0).unwrap();
+        c.fill_col_with(2, BrailleChar::with_data(0b11111111)).unwrap();
+
+        assert_eq!(c.cell_state(0, 0).unwrap(), CellState::Partial);
+        assert_eq!(c.cell_state(1, 0).unwrap(), CellState::Empty);
+        assert_eq!(c.cell_state(2, 0).unwrap(), CellState::Full);
+        assert!(c.cell_state(3, 0).is_err());
+    }
+
+    #[test]
+    fn canvas_to_string_hybrid() {
+        let mut c = Canvas::with_size(6, 4);
+        c.set(0, 0).unwrap();
+        c.fill_col_with(2, BrailleChar::with_data(0b11111111)).unwrap();
+
+        assert_eq!(c.to_string_hybrid(), "⠁⠀█");
+        assert_eq!(c.to_string(), "⠁⠀⣿");
+    }
+
+    #[test]
+    fn canvas_coords_to_index_overflow() {
+        let c = Canvas::with_size(7, 10);
+        assert_eq!(c.coords_to_index(usize::MAX, usize::MAX), None);
+        assert_eq!(c.coords_to_index(0, usize::MAX - 3), None);
+        assert_eq!(c.coords_to_index(usize::MAX - 1, 0), None);
+    }
+
+    #[test]
+    fn canvas_set_normalized() {
+        let mut c = Canvas::with_size(7, 10);
+        c.set_normalized(0.0, 0.0).unwrap();
+        c.set_normalized(1.0, 1.0).unwrap();
+        c.set_normalized(0.5, 0.5).unwrap();
+        assert!(c.get(0, 0).unwrap());
+        assert!(c.get(6, 9).unwrap());
+        assert!(c.get(3, 5).unwrap());
+
+        assert!(c.set_normalized(1.1, 0.0).is_err());
+        assert!(c.set_normalized(0.0, -0.1).is_err());
+        assert!(c.set_normalized(f64::NAN, 0.0).is_err());
+
+        let mut c = Canvas::with_size(7, 10);
+        c.set_normalized_clamped(-3.0, 2.0).unwrap();
+        assert_eq!(c.bounds(), Some((0, 9, 0, 9)));
+        assert!(c.set_normalized_clamped(f64::NAN, 0.0).is_err());
+        assert!(Canvas::new().set_normalized_clamped(0.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn canvas_cubic_bezier() {
+        let mut c = Canvas::with_size(40, 20);
+        c.cubic_bezier((0, 19), (10, 0), (30, 0), (39, 19), 0).unwrap();
+        println!("{c}");
+        assert!(c.get(0, 19).unwrap());
+        assert!(c.get(39, 19).unwrap());
+        assert_eq!(c.bounds().map(|b| b.1), Some(5));
+
+        let mut sampled = Canvas::with_size(40, 20);
+        sampled.cubic_bezier((0, 19), (10, 0), (30, 0), (39, 19), 200).unwrap();
+        assert_eq!(sampled.bounds(), c.bounds());
+
+        let mut c = Canvas::with_size(10, 10);
+        c.cubic_bezier((0, 0), (100, 100), (200, 0), (9, 9), 8).unwrap();
+        assert!(c.get(9, 9).unwrap());
+
+        let mut c = Canvas::with_size(10, 10);
+        c.cubic_bezier((3, 3), (3, 3), (3, 3), (3, 3), 0).unwrap();
+        assert_eq!(c.bounds(), Some((3, 3, 3, 3)));
+
+        let mut c = Canvas::with_size(10, 10);
+        c.cubic_bezier((0, 0), (1_000_000_000, 0), (0, 1_000_000_000), (9, 9), 4).unwrap();
+        assert!(c.get(0, 0).unwrap());
+        assert!(c.get(9, 9).unwrap());
+
+        let mut c = Canvas::with_size(10, 10);
+        c.cubic_bezier((0, 0), (usize::MAX, usize::MAX), (usize::MAX, 0), (9, 9), 0).unwrap();
+        assert!(c.get(0, 0).unwrap());
+        assert!(c.get(9, 9).unwrap());
+
+        assert!(c.cubic_bezier((0, 0), (0, 0), (0, 0), (10, 0), 4).is_err());
+    }
+
+    #[test]
+    fn canvas_atlas() {
+        let mut a = Canvas::with_size(2, 4);
+        a.set_all();
+        let mut b = Canvas::with_size(3, 2);
+        b.set(2, 1).unwrap();
+        let c = Canvas::with_size(1, 1);
+
+        let (atlas, placements) = Canvas::atlas(&[&a, &b, &c], 2, 1);
+        assert_eq!((atlas.x, atlas.y), (7, 9));
+        assert_eq!(placements, vec![(0, 0, 2, 4), (4, 0, 3, 2), (0, 5, 1, 1)]);
+        assert!(atlas.get(6, 1).unwrap());
+        assert!(atlas.get(1, 3).unwrap());
+        assert_eq!(atlas.set_points().count(), 9);
+
+        assert_eq!(Canvas::atlas(&[], 3, 1), (Canvas::new(), vec![]));
+    }
+
+    #[test]
+    fn canvas_retain_points() {
+        let mut c = Canvas::with_size(7, 10);
+        c.set_all();
+        c.retain_points(|_, y| y < 5);
+
+        assert_eq!(c.bounds(), Some((0, 0, 6, 4)));
+        assert_eq!(c.set_points().count(), 35);
+    }
+
+    #[test]
+    fn canvas_strict() {
+        let mut c = Canvas::with_size(7, 10);
+        c.set_strict(true);
+        c.set_normalized_clamped(1.0, 0.0).unwrap();
+
+        c.set_strict(false);
+        c.set_normalized_clamped(2.0, 0.0).unwrap();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn canvas_strict_panics() {
+        let mut c = Canvas::with_size(7, 10);
+        c.set_strict(true);
+        let _ = c.set_normalized_clamped(2.0, 0.0);
+    }
+
+    #[test]
+    fn canvas_render_fmt() {
+        let mut c = Canvas::with_size(7, 10);
+        c.set(0, 0).unwrap();
+        c.set(6, 9).unwrap();
+
+        let mut string = String::from("> ");
+        c.render_fmt(&mut string).unwrap();
+        assert_eq!(string, format!("> {c}"));
+        assert_eq!(c.to_string(), "⠁⠀⠀⠀\n⠀⠀⠀⠀\n⠀⠀⠀⠂");
+    }
+
+    #[test]
+    fn canvas_for_each_cell_mut() {
+        let mut c = Canvas::with_size(4, 4);
+        c.set(0, 0).unwrap();
+
+        c.for_each_cell_mut(|cell| *cell ^= 0b11111111);
+        assert_eq!(c.to_string(), "⣾⣿");
+
+        let mut count = 0;
+        c.for_each_cell_mut(|cell| {
+            *cell |= 0x00ff00 << 8;
+            count += 1;
+        });
+        assert_eq!(count, 2);
+        assert!(c.to_html().contains("color:#00ff00"));
+    }
+
+    #[test]
+    fn canvas_scroll_subcell() {
+        let mut c = Canvas::with_size(4, 8);
+        c.set(0, 0).unwrap();
+        c.set(1, 3).unwrap();
+        c.set(3, 7).unwrap();
+
+        c.scroll_subcell(1, 3);
+        let mut expected = Canvas::with_size(4, 8);
+        expected.set(1, 3).unwrap();
+        expected.set(2, 6).unwrap();
+        assert_eq!(c, expected);
+
+        c.scroll_subcell(0, 0);
+        assert_eq!(c, expected);
+
+        c.scroll_subcell(usize::MAX, 1);
+        assert_eq!(c.bounds(), None);
+    }
+
+    #[test]
+    fn canvas_connected_components() {
+        let mut c = Canvas::with_size(7, 10);
+        c.set(0, 0).unwrap();
+        c.set(1, 0).unwrap();
+        c.set(2, 1).unwrap();
+        c.set(5, 5).unwrap();
+        c.set(5, 6).unwrap();
+        c.set(6, 6).unwrap();
+
+        let four = c.connected_components(Connectivity::Four);
+        assert_eq!(four.len(), 3);
+        assert_eq!(four[0], vec![(0, 0), (1, 0)]);
+        assert_eq!(four[1], vec![(2, 1)]);
+        assert_eq!(four[2].len(), 3);
+
+        let eight = c.connected_components(Connectivity::Eight);
+        assert_eq!(eight.len(), 2);
+        assert_eq!(eight[0], vec![(0, 0), (1, 0), (2, 1)]);
+
+        assert!(Canvas::new().connected_components(Connectivity::Four).is_empty());
+    }
+
+    #[test]
+    fn canvas_tiled() {
+        let mut motif = Canvas::with_size(3, 2);
+        motif.set(0, 0).unwrap();
+        motif.set(2, 1).unwrap();
+
+        let c = motif.tiled(7, 5);
+        assert_eq!((c.x, c.y), (7, 5));
+        let mut points: Vec<(usize, usize)> = c.set_points().collect();
+        points.sort_by_key(|&(x, y)| (y, x));
+        assert_eq!(points, vec![
+            (0, 0), (3, 0), (6, 0),
+            (2, 1), (5, 1),
+            (0, 2), (3, 2), (6, 2),
+            (2, 3), (5, 3),
+            (0, 4), (3, 4), (6, 4),
+        ]);
+
+        assert_eq!(Canvas::new().tiled(4, 4), Canvas::with_size(4, 4));
+    }
+
+    #[test]
+    fn canvas_cell_bytes_with_order() {
+        let mut c = Canvas::with_size(4, 4);
+        c.set(1, 0).unwrap();
+        c.set(0, 3).unwrap();
+        c.set(3, 3).unwrap();
+
+        assert_eq!(c.cell_bytes_with_order(DotOrder::Unicode), c.cell_bytes());
+        assert_eq!(c.cell_bytes_with_order(DotOrder::Unicode), vec![0b01001000, 0b10000000]);
+        assert_eq!(c.cell_bytes_with_order(DotOrder::Linear), vec![0b01000010, 0b10000000]);
+
+        let mut c = Canvas::with_dot_map(4, 4, [128, 64, 32, 16, 8, 4, 2, 1]).unwrap();
+        c.set(1, 0).unwrap();
+        c.set(0, 3).unwrap();
+        c.set(3, 3).unwrap();
+
+        assert_eq!(c.cell_bytes_with_order(DotOrder::Unicode), vec![0b01001000, 0b10000000]);
+        assert_eq!(c.cell_bytes_with_order(DotOrder::Linear), vec![0b01000010, 0b10000000]);
+        assert_eq!(c.cell_bytes(), vec![0b01001000, 0b10000000]);
+    }
+
+    #[test]
+    fn canvas_to_string_indented() {
+        let mut c = Canvas::with_size(2, 8);
+        c.set(0, 0).unwrap();
+
+        assert_eq!(c.to_string_indented(2), "  ⠁\n  ⠀");
+        assert_eq!(c.to_string_indented(0), c.to_string());
+        assert_eq!(Canvas::new().to_string_indented(4), "");
+    }
+
+    #[test]
+    fn canvas_snapshot_restore() {
+        let mut c = Canvas::with_size(7, 10);
+        c.set(0, 0).unwrap();
+        let snap = c.snapshot();
+        let before = c.clone();
+
+        c.set(6, 9).unwrap();
+        c.reset(0, 0).unwrap();
+        c.restore(&snap).unwrap();
+        assert_eq!(c, before);
+
+        assert!(Canvas::with_size(7, 11).restore(&snap).is_err());
+
+        let mut mapped = Canvas::with_dot_map(7, 10, [128, 64, 32, 16, 8, 4, 2, 1]).unwrap();
+        mapped.restore(&snap).unwrap();
+        assert_eq!(mapped, before);
+
+        let mut bottom = Canvas::with_origin(7, 10, Origin::BottomLeft);
+        bottom.set(0, 0).unwrap();
+        let snap = bottom.snapshot();
+        let mut c = Canvas::with_dot_map(7, 10, [128, 64, 32, 16, 8, 4, 2, 1]).unwrap();
+        c.restore(&snap).unwrap();
+        assert_eq!(c, bottom);
+        assert!(c.get(0, 0).unwrap());
+    }
+
+    #[test]
+    fn canvas_cell_chars() {
+        let mut c = Canvas::with_size(4, 8);
+        c.set(3, 4).unwrap();
+
+        let cells: Vec<(usize, usize, char)> = c.cell_chars().collect();
+        assert_eq!(cells, vec![(0, 0, '⠀'), (1, 0, '⠀'), (0, 1, '⠀'), (1, 1, '⠈')]);
+        assert_eq!(Canvas::new().cell_chars().count(), 0);
+    }
+
+    #[test]
+    fn canvas_with_origin() {
+        let mut c = Canvas::with_origin(4, 6, Origin::BottomLeft);
+        c.set(0, 0).unwrap();
+        c.set(3, 5).unwrap();
+        assert!(c.get(0, 0).unwrap());
+        assert_eq!(c.bounds(), Some((0, 0, 3, 5)));
+        assert_eq!(c.char_at(0, 1), Some('⠂'));
+        assert_eq!(c.char_at(1, 0), Some('⠈'));
+
+        c.flip(0, 0).unwrap();
+        c.reset(3, 5).unwrap();
+        assert_eq!(c.bounds(), None);
+        assert!(c.set(0, 6).is_err());
+
+        c.set(0, 0).unwrap();
+        c.scroll_subcell(1, 0);
+        assert_eq!(c.bounds(), Some((1, 5, 1, 5)));
+        assert!(c.get(1, 0).unwrap());
+
+        assert_eq!(Canvas::with_origin(4, 6, Origin::TopLeft), Canvas::with_size(4, 6));
+    }
+
+    #[test]
+    fn canvas_ring() {
+        let mut c = Canvas::with_size(6, 8);
+        c.ring(2, 3, 1, 1).unwrap();
+        assert_eq!(c.to_string(), "⢀⢄⠀\n⠀⠁⠀");
+
+        let mut c = Canvas::with_size(6, 8);
+        c.ring(2, 3, 0, 2).unwrap();
+        assert_eq!(c.bounds(), Some((0, 1, 4, 5)));
+        assert!(c.get(2, 3).unwrap());
+        assert!(!c.get(0, 1).unwrap());
+
+        assert!(c.ring(2, 3, 3, 2).is_err());
+        assert!(c.ring(6, 0, 0, 1).is_err());
+
+        let mut c = Canvas::with_size(6, 8);
+        c.ring(5, 5, 0, usize::MAX / 2).unwrap();
+        assert_eq!(c.to_string(), "⣿⣿⣿\n⣿⣿⣿");
+        c.reset_all();
+        c.ring(5, 5, usize::MAX / 2, usize::MAX).unwrap();
+        assert_eq!(c.to_string(), "⠀⠀⠀\n⠀⠀⠀");
+    }
+
+    #[test]
+    fn canvas_with_char_size() {
+        let c = Canvas::with_char_size(3, 2);
+        assert_eq!(c.size(), (6, 8));
+        assert_eq!((c.char_x(), c.char_y()), (3, 2));
+        assert_eq!(c, Canvas::with_size(6, 8));
+    }
+
+    #[test]
+    fn canvas_intersects() {
+        let mut c = Canvas::with_size(8, 8);
+        c.set(5, 6).unwrap();
+
+        let mut sprite = Canvas::with_size(2, 2);
+        sprite.set(1, 1).unwrap();
+
+        assert!(c.intersects(&sprite, 4, 5));
+        assert!(!c.intersects(&sprite, 5, 5));
+        assert!(!c.intersects(&sprite, 7, 7));
+        assert!(!c.intersects(&sprite, usize::MAX, 0));
+        assert!(!c.intersects(&Canvas::with_size(2, 2), 4, 5));
+    }
+
+    #[test]
+    fn canvas_convex_hull() {
+        let mut c = Canvas::with_size(6, 8);
+        c.convex_hull(&[(0, 0), (4, 0), (2, 2), (4, 4), (0, 4), (1, 3)]).unwrap();
+
+        let mut expected = Canvas::with_size(6, 8);
+        for i in 0..5 {
+            for (x, y) in [(i, 0), (i, 4), (0, i), (4, i)] {
+                expected.set(x, y).unwrap();
+            }
+        }
+        assert_eq!(c, expected);
+
+        let mut c = Canvas::with_size(6, 8);
+        c.convex_hull(&[(1, 1), (3, 1), (2, 1)]).unwrap();
+        assert_eq!(c.bounds(), Some((1, 1, 3, 1)));
+
+        let mut c = Canvas::with_size(6, 8);
+        c.convex_hull(&[(2, 5), (2, 5)]).unwrap();
+        assert_eq!(c.bounds(), Some((2, 5, 2, 5)));
+
+        c.convex_hull(&[]).unwrap();
+        assert!(c.convex_hull(&[(0, 0), (6, 0)]).is_err());
+    }
+
+    #[test]
+    fn canvas_kaleidoscope() {
+        let mut c = Canvas::with_size(4, 8);
+        c.set(0, 0).unwrap();
+        c.set(1, 2).unwrap();
+        c.set(3, 7).unwrap();
+        assert_eq!(c.kaleidoscope().to_string(), "⠡⠌\n⡐⢂");
+
+        let mut c = Canvas::with_size(3, 5);
+        c.set(1, 2).unwrap();
+        c.set(0, 1).unwrap();
+        let k = c.kaleidoscope();
+        assert_eq!(k.set_points().collect::<Vec<_>>(), vec![(0, 1), (0, 3), (1, 2), (2, 1), (2, 3)]);
+    }
+
+    #[test]
+    fn canvas_to_string_annotated() {
+        let mut c = Canvas::with_size(4, 8);
+        c.set_all();
+        c.reset(3, 7).unwrap();
+        (0..4).for_each(|y| c.reset(2, y).unwrap());
+
+        assert_eq!(c.to_string_annotated(), "⣿⢸\n0xFF 0xB8\n⣿⡿\n0xFF 0x7F");
+        assert_eq!(Canvas::new().to_string_annotated(), "");
+    }
+
+    #[test]
+    fn canvas_blit_wrap_x() {
+        let mut text = Canvas::with_size(6, 4);
+        text.set(0, 0).unwrap();
+        text.set(1, 1).unwrap();
+        text.set(5, 3).unwrap();
+
+        for dx in 0..13 {
+            let mut c = Canvas::with_size(6, 4);
+            c.blit_wrap_x(&text, dx).unwrap();
+
+            let expected: Vec<(usize, usize)> = [(0, 0), (1, 1), (5, 3)]
+                .iter()
+                .map(|&(x, y)| ((x + dx) % 6, y))
+                .collect();
+            for y in 0..4 {
+                for x in 0..6 {
+                    assert_eq!(c.get(x, y).unwrap(), expected.contains(&(x, y)));
+                }
+            }
+        }
+
+        let mut c = Canvas::with_size(6, 4);
+        c.blit_wrap_x(&text, 1).unwrap();
+        assert_eq!(c.to_string(), "⡈⠂⠀");
+        assert!(c.blit_wrap_x(&Canvas::with_size(6, 8), 0).is_err());
+    }
+
+    #[test]
+    fn canvas_with_caption() {
+        let c = Canvas::with_char_size(6, 1);
+        assert_eq!(c.with_caption("abc", CaptionOverflow::Wrap), "⠀⠀⠀⠀⠀⠀\n abc");
+        assert_eq!(c.with_caption("cut off here", CaptionOverflow::Truncate), "⠀⠀⠀⠀⠀⠀\ncut of");
+        assert_eq!(
+            c.with_caption("a sample caption", CaptionOverflow::Wrap),
+            "⠀⠀⠀⠀⠀⠀\n  a\nsample\ncaptio\n  n"
+        );
+        assert_eq!(Canvas::new().with_caption("title", CaptionOverflow::Wrap), "\ntitle");
+    }
+
+    #[test]
+    fn canvas_from_function() {
+        let c = Canvas::from_function(4, 4, |x| x, (0.0, 3.0), Some((0.0, 3.0)));
+        assert_eq!(c.to_string(), "⡠⠊");
+
+        let c = Canvas::from_function(4, 4, |x| if x == 1.0 { f64::NAN } else { 2.0 * x }, (0.0, 3.0), None);
+        assert_eq!(c.set_points().collect::<Vec<_>>(), vec![(0, 3), (2, 1), (3, 0)]);
+
+        assert_eq!(Canvas::from_function(0, 4, |x| x, (0.0, 1.0), None), Canvas::with_size(0, 4));
+        assert_eq!(Canvas::from_function(4, 0, |x| x, (0.0, 1.0), None), Canvas::with_size(4, 0));
+    }
+
+    #[test]
+    fn canvas_morph() {
+        let cross = [[false, true, false], [true, true, true], [false, true, false]];
+
+        let mut dot = Canvas::with_size(6, 4);
+        dot.set(2, 1).unwrap();
+        let dilated = dot.morph(&cross, MorphOp::Dilate);
+        assert_eq!(dilated.set_points().collect::<Vec<_>>(), vec![(1, 1), (2, 0), (2, 1), (2, 2), (3, 1)]);
+        assert_eq!(dot.morph(&cross, MorphOp::Erode), Canvas::with_size(6, 4));
+
+        let mut block = Canvas::with_size(6, 4);
+        block.set_all();
+        let eroded = block.morph(&[[true; 3]; 3], MorphOp::Erode);
+        assert_eq!(eroded.set_points().collect::<Vec<_>>(), vec![(1, 1), (1, 2), (2, 1), (2, 2), (3, 1), (3, 2), (4, 1), (4, 2)]);
+        assert_eq!(block.morph(&[[true; 3]; 3], MorphOp::Dilate), block);
+    }
+
+    #[test]
+    fn canvas_set_path() {
+        let mut c = Canvas::with_size(6, 4);
+        c.set_path((0, 0), &[(2, 0), (0, 2), (-1, 1)], false).unwrap();
+        assert_eq!(c.set_points().collect::<Vec<_>>(), vec![(0, 0), (1, 3), (2, 0), (2, 2)]);
+
+        let mut c = Canvas::with_size(6, 4);
+        c.set_path((0, 0), &[(2, 0), (0, 2)], true).unwrap();
+        assert_eq!(c.to_string(), "⠉⠇⠀");
+
+        let mut c = Canvas::with_size(6, 4);
+        let err = c.set_path((0, 0), &[(1, 1), (5, 0), (0, 1)], false).unwrap_err();
+        assert!(matches!(err, IndexError::PathStep { step: 1, .. }));
+        let err = c.set_path((0, 0), &[(-1, 0)], true).unwrap_err();
+        assert!(matches!(err, IndexError::PathStep { step: 0, .. }));
+        assert!(c.set_path((6, 0), &[], false).is_err());
+        assert_eq!(c.bounds(), None);
+    }
+
+    #[test]
+    fn canvas_outline() {
+        let mut c = Canvas::with_size(8, 8);
+        for y in 1..7 {
+            for x in 1..6 {
+                c.set(x, y).unwrap();
+            }
+        }
+
+        let mut expected = Canvas::with_size(8, 8);
+        for y in 1..7 {
+            for x in 1..6 {
+                if x == 1 || x == 5 || y == 1 || y == 6 {
+                    expected.set(x, y).unwrap();
+                }
+            }
+        }
+        assert_eq!(c.outline(), expected);
+
+        let mut full = Canvas::with_size(4, 4);
+        full.set_all();
+        assert_eq!(full.outline().to_string(), "⣏⣹");
+    }
+
+    #[test]
+    fn canvas_dot_roundtrip() {
+        for width in 0..=13 {
+            for height in 0..=13 {
+                let c = Canvas::with_size(width, height);
+
+                for y in 0..height {
+                    for x in 0..width {
+                        assert!(c.dot_roundtrip(x, y), "{x}, {y} on {width}x{height}");
+                    }
+                }
+                assert!(!c.dot_roundtrip(width, 0));
+                assert!(!c.dot_roundtrip(0, height));
+            }
+        }
+    }
+
+    #[test]
+    fn canvas_dot_roundtrip_fuzz() {
+        let mut state = 0x5eed;
+        let map = [16, 1, 128, 2, 8, 64, 4, 32];
+
+        for _ in 0..200 {
+            let width = (splitmix64(&mut state) % 97) as usize + 1;
+            let height = (splitmix64(&mut state) % 97) as usize + 1;
+            let mut c = Canvas::with_dot_map(width, height, map).unwrap();
+
+            for _ in 0..50 {
+                let x = (splitmix64(&mut state) % width as u64) as usize;
+                let y = (splitmix64(&mut state) % height as u64) as usize;
+                assert!(c.dot_roundtrip(x, y), "{x}, {y} on {width}x{height}");
+
+                c.set(x, y).unwrap();
+                assert!(c.get(x, y).unwrap());
+                assert!(c.set_points().any(|point| point == (x, y)));
+            }
+        }
+    }
+
+    #[test]
+    fn canvas_viewport_string() {
+        let mut c = Canvas::with_char_size(3, 2);
+        c.set(2, 0).unwrap();
+        c.set(5, 7).unwrap();
+
+        assert_eq!(c.viewport_string(0, 0, 3, 2), c.to_string());
+        assert_eq!(c.viewport_string(1, 0, 2, 1), "⠁⠀");
+        assert_eq!(c.viewport_string(2, 1, 2, 2), "⢀⠀\n⠀⠀");
+        assert_eq!(c.viewport_string(usize::MAX, 0, 2, 1), "⠀⠀");
+        assert_eq!(c.viewport_string(0, 0, 0, 0), "");
+        assert_eq!(c.viewport_string(0, 0, usize::MAX, 0), "");
+        assert_eq!(Canvas::with_char_size(1, 1).viewport_string(0, 0, 2, 1), "⠀⠀");
+    }
+
+    #[test]
+    fn canvas_rle() {
+        let mut c = Canvas::with_size(5, 6);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2), (4, 5)] {
+            c.set(x, y).unwrap();
+        }
+
+        let rle = c.to_rle();
+        assert_eq!(rle, "x = 5, y = 6\nbo$2bo$3o3$4bo!");
+        assert_eq!(Canvas::from_rle(&rle).unwrap(), c);
+        assert_eq!(Canvas::with_size(3, 2).to_rle(), "x = 3, y = 2\n!");
+
+        let glider = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$\n3o!";
+        assert_eq!(Canvas::from_rle(glider).unwrap().to_rle(), "x = 3, y = 3\nbo$2bo$3o!");
+
+        assert!(Canvas::from_rle("").is_err());
+        assert!(Canvas::from_rle("x = 2, y = 2\n3o!").is_err());
+        assert!(Canvas::from_rle("x = 2, y = 2\no$o$o!").is_err());
+        assert!(Canvas::from_rle("x = 2, y = 2\noo").is_err());
+        assert!(Canvas::from_rle("x = 2, y = 2\n2x!").is_err());
+        assert!(matches!(
+            Canvas::from_rle("x = 4000000000, y = 4000000000\n!"),
+            Err(IndexError::InvalidFormat("rle size too large"))
+        ));
+    }
+
+    #[test]
+    fn canvas_centroid() {
+        let mut c = Canvas::with_size(8, 8);
+        assert_eq!(c.centroid(), None);
+
+        c.set(1, 1).unwrap();
+        assert_eq!(c.centroid(), Some((1.0, 1.0)));
+
+        c.set(6, 2).unwrap();
+        c.set(2, 6).unwrap();
+        assert_eq!(c.centroid(), Some((3.0, 3.0)));
+    }
+
+    #[test]
+    fn canvas_clear_cell() {
+        let mut c = Canvas::with_size(4, 4);
+        c.set_all();
+        c.for_each_cell_mut(|cell| *cell |= 0xff0000 << 8);
+
+        c.clear_cell(1, 0).unwrap();
+        assert_eq!(c.to_string(), "⣿⠀");
+        assert_eq!(c.data[1], 0xff0000 << 8);
+        assert!(c.clear_cell(2, 0).is_err());
+        assert!(c.clear_cell(0, 1).is_err());
+    }
+
+    #[test]
+    fn canvas_transforms_keep_origin() {
+        let mut c = Canvas::with_origin(6, 8, Origin::BottomLeft);
+        c.set_strict(true);
+        c.set(0, 0).unwrap();
+        c.set(0, 7).unwrap();
+        c.set(3, 2).unwrap();
+        c.set(5, 7).unwrap();
+
+        let center = [[false, false, false], [false, true, false], [false, false, false]];
+        let kept = |t: &Canvas| t.origin == Origin::BottomLeft && t.strict;
+
+        assert_eq!(c.map_points(|x, y| Some((x, y))), c);
+        assert!(c.map_points(|x, y| Some((x + 1, y))).get(1, 0).unwrap());
+        assert_eq!(c.padded(0, 0, 0, 0), c);
+        assert!(c.padded(1, 0, 0, 2).get(1, 2).unwrap());
+        assert_eq!(c.tiled(6, 8), c);
+        assert_eq!(c.tiles(6, 8), vec![c.clone()]);
+        assert_eq!(c.rotate_90_cw().rotate_90_ccw(), c);
+        assert_eq!(c.rotate_90_ccw().rotate_90_cw(), c);
+        assert!(kept(&c.rotate_90_cw()));
+        assert_eq!(c.morph(&center, MorphOp::Erode), c);
+        assert_eq!(c.morph(&center, MorphOp::Dilate), c);
+        assert_eq!(c.outline(), c);
+        assert!(kept(&c.kaleidoscope()));
+        assert_eq!(c.kaleidoscope().kaleidoscope(), c.kaleidoscope());
+        assert!(c.kaleidoscope().get(5, 0).unwrap());
+        assert!(kept(&c.downsample_2x()));
+        assert!(c.downsample_2x().get(0, 0).unwrap());
+        assert!(c.downsample_2x().get(2, 3).unwrap());
+        assert_eq!(c.symmetric_difference(&c.blank_like()).unwrap(), c);
+        assert_eq!(c.xor_into_new(&c.blank_like()).unwrap(), c);
+        assert_eq!(Canvas::composite(&[&c, &c.blank_like()]).unwrap(), c);
+    }
+
+    #[test]
+    fn canvas_to_ansi_zebra() {
+        let mut c = Canvas::with_size(2, 12);
+        c.set(0, 0).unwrap();
+
+        assert_eq!(
+            c.to_ansi_zebra((0, 0, 0), (40, 40, 40)),
+            "\x1b[48;2;0;0;0m⠁\x1b[0m\n\x1b[48;2;40;40;40m⠀\x1b[0m\n\x1b[48;2;0;0;0m⠀\x1b[0m"
+        );
+        assert_eq!(Canvas::new().to_ansi_zebra((0, 0, 0), (1, 1, 1)), "");
+    }
+
+    #[test]
+    fn canvas_to_ansi_string_with_blink() {
+        let mut c = Canvas::with_size(6, 8);
+        c.set(0, 0).unwrap();
+        assert_eq!(c.to_ansi_string_with_blink(), c.to_string());
+
+        c.set_blink(0, 0, true).unwrap();
+        c.set_blink(1, 0, true).unwrap();
+        c.set_blink(1, 1, true).unwrap();
+        assert_eq!(c.to_ansi_string_with_blink(), "\x1b[5m⠁⠀\x1b[25m⠀\n⠀\x1b[5m⠀\x1b[25m⠀");
+        assert_eq!(c.to_string(), "⠁⠀⠀\n⠀⠀⠀");
+
+        c.set_blink(1, 0, false).unwrap();
+        let mut ch = BrailleChar::with_data(0b00000001);
+        ch.set_blink(true);
+        c.fill_col_with(2, ch).unwrap();
+        assert_eq!(c.to_ansi_string_with_blink(), "\x1b[5m⠁\x1b[25m⠀\x1b[5m⠁\x1b[25m\n⠀\x1b[5m⠀⠁\x1b[25m");
+
+        let snap = c.snapshot();
+        let blinking = c.to_ansi_string_with_blink();
+        c.set_blink(0, 0, false).unwrap();
+        c.restore(&snap).unwrap();
+        assert_eq!(c.to_ansi_string_with_blink(), blinking);
+
+        let blank = Canvas::with_size(6, 8);
+        assert_eq!(Canvas::composite(&[&blank, &c]).unwrap().to_ansi_string_with_blink(), blinking);
+
+        assert!(c.set_blink(3, 0, true).is_err());
+        assert!(c.set_blink(0, 2, true).is_err());
+        assert_eq!(Canvas::new().to_ansi_string_with_blink(), "");
+
+        let mut c = Canvas::with_size(2, 4);
+        c.set(0, 0).unwrap();
+        c.for_each_cell_mut(|cell| *cell |= 0x0000ff << 8);
+        assert_eq!(c.to_ansi_string_with_blink(), "⠁");
+        assert_eq!(c.to_html(), "<pre><span style=\"color:#0000ff\">⠁</span></pre>");
+    }
+
+    #[test]
+    fn canvas_tiles() {
+        let mut c = Canvas::with_size(5, 6);
+        c.set(0, 0).unwrap();
+        c.set(3, 1).unwrap();
+        c.set(4, 5).unwrap();
+
+        let tiles = c.tiles(2, 4);
+        assert_eq!(tiles.len(), 6);
+        assert!(tiles.iter().all(|tile| tile.size() == (2, 4)));
+        assert_eq!(
+            tiles.iter().map(|tile| tile.to_string()).collect::<Vec<String>>(),
+            vec!["⠁", "⠐", "⠀", "⠀", "⠀", "⠂"]
+        );
+
+        assert!(c.tiles(0, 4).is_empty());
+        assert!(Canvas::new().tiles(2, 4).is_empty());
+    }
+
+    #[test]
+    fn canvas_xor_into_new() {
+        let mut a = Canvas::with_size(4, 4);
+        a.set(0, 0).unwrap();
+        a.set(3, 3).unwrap();
+        let mut b = Canvas::with_size(4, 4);
+        b.set(3, 3).unwrap();
+        b.set(2, 0).unwrap();
+
+        assert_eq!(a.xor_into_new(&b).unwrap().to_string(), "⠁⠁");
+        assert!(a.xor_into_new(&Canvas::with_size(4, 8)).is_err());
+    }
+
+    #[test]
+    fn canvas_padded() {
+        let mut c = Canvas::with_size(2, 2);
+        c.set(0, 0).unwrap();
+        c.set(1, 1).unwrap();
+
+        let p = c.padded(1, 2, 3, 0);
+        assert_eq!(p.size(), (5, 5));
+        assert_eq!(p.set_points().collect::<Vec<_>>(), vec![(1, 3), (2, 4)]);
+        assert_eq!(c.padded(0, 0, 0, 0), c);
     }
 }
\ No newline at end of file
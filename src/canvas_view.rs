@@ -0,0 +1,105 @@
+use crate::{
+    canvas::Canvas,
+    error::IndexError,
+};
+
+
+/// Mutable view into a rectangular region of a `Canvas`, created by `Canvas::view_mut()`.
+/// Point 0, 0 of the view is the top left point of the region.
+#[derive(Debug)]
+pub struct CanvasView<'a> {
+    canvas: &'a mut Canvas,
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+}
+
+
+impl<'a> CanvasView<'a> {
+    /// Creates a new view, clipping the region to the canvas.
+    pub(crate) fn new(canvas: &'a mut Canvas, x: usize, y: usize, w: usize, h: usize) -> Self {
+        let (canvas_x, canvas_y) = canvas.size();
+        let w = w.min(canvas_x.saturating_sub(x));
+        let h = h.min(canvas_y.saturating_sub(y));
+
+        CanvasView {
+            canvas,
+            x,
+            y,
+            w,
+            h,
+        }
+    }
+
+    /// Returns the size of the view in points as (width, height).
+    pub fn size(&self) -> (usize, usize) {
+        (self.w, self.h)
+    }
+
+    /// Translates a point of the view into a point of the canvas.
+    /// Views always use the top left as origin, even if the canvas doesn't.
+    /// If the point is outside of the view returns an `IndexError`.
+    fn translate(&self, x: usize, y: usize) -> Result<(usize, usize), IndexError> {
+        if x < self.w && y < self.h {
+            Ok((self.x + x, self.canvas.origin_y(self.y + y)))
+        } else {
+            Err(IndexError::USizeMatrix(self.w, self.h, x, y))
+        }
+    }
+
+    /// Returns whether the point at a given x, y position of the view is set. 0, 0 is top left.
+    /// If coordinates are out of range returns an `IndexError`.
+    pub fn get(&self, x: usize, y: usize) -> Result<bool, IndexError> {
+        let (x, y) = self.translate(x, y)?;
+        self.canvas.get(x, y)
+    }
+
+    /// Sets the point at a given x, y position of the view. 0, 0 is top left.
+    /// If coordinates are out of range returns an `IndexError` otherwise `OK()`.
+    pub fn set(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
+        let (x, y) = self.translate(x, y)?;
+        self.canvas.set(x, y)
+    }
+
+    /// Resets the point at a given x, y position of the view. 0, 0 is top left.
+    /// If coordinates are out of range returns an `IndexError` otherwise `OK()`.
+    pub fn reset(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
+        let (x, y) = self.translate(x, y)?;
+        self.canvas.reset(x, y)
+    }
+
+    /// Flips the point at a given x, y position of the view. 0, 0 is top left.
+    /// If coordinates are out of range returns an `IndexError` otherwise `OK()`.
+    pub fn flip(&mut self, x: usize, y: usize) -> Result<(), IndexError> {
+        let (x, y) = self.translate(x, y)?;
+        self.canvas.flip(x, y)
+    }
+}
+
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn canvas_view_translate() {
+        let mut c = Canvas::with_size(7, 10);
+
+        let mut view = c.view_mut(2, 4, 3, 3);
+        assert_eq!(view.size(), (3, 3));
+        view.set(0, 0).unwrap();
+        view.set(2, 2).unwrap();
+        view.flip(1, 1).unwrap();
+        view.reset(1, 1).unwrap();
+        assert!(view.get(2, 2).unwrap());
+        assert!(view.set(3, 0).is_err());
+        assert!(view.set(0, 3).is_err());
+
+        assert_eq!(c.bounds(), Some((2, 4, 4, 6)));
+
+        let view = c.view_mut(5, 8, 10, 10);
+        assert_eq!(view.size(), (2, 2));
+        assert_eq!(c.view_mut(20, 20, 5, 5).size(), (0, 0));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,87 @@
+use crate::canvas::Canvas;
+
+
+/// Holds two canvases of the same size for flicker free animation.
+/// Frames get drawn onto the back buffer, while the front buffer holds what was presented last.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DoubleBuffer {
+    front: Canvas,
+    back: Canvas,
+}
+
+
+impl DoubleBuffer {
+    /// Creates a new DoubleBuffer where both canvases are sized `x` by `y`.
+    pub fn with_size(x: usize, y: usize) -> Self {
+        DoubleBuffer {
+            front: Canvas::with_size(x, y),
+            back: Canvas::with_size(x, y),
+        }
+    }
+
+    /// Returns the back buffer to draw the next frame on.
+    /// After presenting, the back buffer holds the frame presented before, so it usually
+    /// needs to be reset before drawing.
+    pub fn draw_frame(&mut self) -> &mut Canvas {
+        &mut self.back
+    }
+
+    /// Returns the front buffer, meaning the frame that was presented last.
+    pub fn front(&self) -> &Canvas {
+        &self.front
+    }
+
+    /// Renders the back buffer and swaps both buffers.
+    pub fn present(&mut self) -> String {
+        let frame = self.back.to_string();
+        self.swap();
+
+        frame
+    }
+
+    /// Renders only the characters of the back buffer that changed since the last frame,
+    /// positioned with ANSI escape codes like `Canvas::write_diff()`, and swaps both buffers.
+    pub fn present_diff(&mut self) -> String {
+        let mut output = Vec::new();
+        self.back.write_diff(&self.front, &mut output).unwrap();
+        self.swap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    /// Swaps both buffers. If the back buffer changed size while drawing,
+    /// the new back buffer gets replaced with a blank canvas of that size.
+    fn swap(&mut self) {
+        std::mem::swap(&mut self.front, &mut self.back);
+
+        if !self.back.same_size(&self.front) {
+            self.back = self.front.blank_like();
+        }
+    }
+}
+
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn double_buffer_present() {
+        let mut buffer = DoubleBuffer::with_size(4, 4);
+
+        buffer.draw_frame().set(0, 0).unwrap();
+        assert_eq!(buffer.present(), "⠁⠀");
+        assert_eq!(buffer.front().to_string(), "⠁⠀");
+
+        let frame = buffer.draw_frame();
+        frame.reset_all();
+        frame.set(0, 0).unwrap();
+        frame.set(3, 3).unwrap();
+        assert_eq!(buffer.present_diff(), "\x1b[1;2H⢀");
+        assert_eq!(buffer.front().to_string(), "⠁⢀");
+
+        buffer.draw_frame().clear();
+        assert_eq!(buffer.present(), "");
+        assert_eq!(buffer.draw_frame(), &Canvas::new());
+    }
+}
\ No newline at end of file
@@ -1,8 +1,51 @@
 use thiserror::Error;
 
+
+/// Result with `IndexError` as the default error, returned by most fallible methods of this crate.
+/// Other errors can still be given, so it doesn't get in the way of `std::result::Result` when glob imported.
+pub type Result<T, E = IndexError> = core::result::Result<T, E>;
+
+
 #[derive(Error, Debug)]
 pub enum IndexError {
     /// (expected x, expected y, found x, found y)
     #[error("invalid index (expected x<{0}, y<{1} but got x={2}, y={3}) ")]
-    USizeMatrix(usize, usize, usize, usize)
+    USizeMatrix(usize, usize, usize, usize),
+    /// (reason)
+    #[error("invalid format ({0})")]
+    InvalidFormat(&'static str),
+    /// (reason)
+    #[error("invalid argument ({0})")]
+    InvalidArgument(&'static str),
+    /// (expected x, expected y, found x, found y)
+    #[error("size mismatch (expected {0}x{1} but got {2}x{3})")]
+    SizeMismatch(usize, usize, usize, usize),
+    /// (layer index, expected x, expected y, found x, found y)
+    #[error("layer {0} has the wrong size (expected {1}x{2} but got {3}x{4})")]
+    LayerMismatch(usize, usize, usize, usize, usize),
+    /// Move number `step` of a path, counting from 0, led to an invalid position.
+    #[error("path step {step} failed")]
+    PathStep {
+        step: usize,
+        #[source]
+        source: Box<IndexError>,
+    },
+    /// Error that happened while running the drawing operation `op`.
+    #[error("{op} failed")]
+    InOperation {
+        op: &'static str,
+        #[source]
+        source: Box<IndexError>,
+    },
+}
+
+
+impl IndexError {
+    /// Wraps the error to note that it happened while running the drawing operation `op`.
+    pub(crate) fn in_operation(self, op: &'static str) -> Self {
+        IndexError::InOperation {
+            op,
+            source: Box::new(self),
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,104 @@
+//! A small set of predefined icons for common UI glyphs.
+//!
+//! Icons are stored as rows of braille characters, so every icon is 4 points tall and
+//! 2 points wide per character.
+//! Use `Canvas::stamp_icon()` to place one on a canvas.
+
+use crate::braille_char::BrailleChar;
+
+
+/// Arrow pointing up. `⢺⠂`
+pub const ARROW_UP: [BrailleChar; 2] = [
+    BrailleChar::with_data(0b10111010),
+    BrailleChar::with_data(0b00000010),
+];
+/// Arrow pointing down. `⢼⠄`
+pub const ARROW_DOWN: [BrailleChar; 2] = [
+    BrailleChar::with_data(0b10111100),
+    BrailleChar::with_data(0b00000100),
+];
+/// Arrow pointing left. `⠺⠒`
+pub const ARROW_LEFT: [BrailleChar; 2] = [
+    BrailleChar::with_data(0b00111010),
+    BrailleChar::with_data(0b00010010),
+];
+/// Arrow pointing right. `⠒⠗`
+pub const ARROW_RIGHT: [BrailleChar; 2] = [
+    BrailleChar::with_data(0b00010010),
+    BrailleChar::with_data(0b00010111),
+];
+/// Heart. `⠳⠞`
+pub const HEART: [BrailleChar; 2] = [
+    BrailleChar::with_data(0b00110011),
+    BrailleChar::with_data(0b00011110),
+];
+/// Checkmark. `⢄⠜`
+pub const CHECK: [BrailleChar; 2] = [
+    BrailleChar::with_data(0b10000100),
+    BrailleChar::with_data(0b00011100),
+];
+/// Cross. `⡱⢎`
+pub const CROSS: [BrailleChar; 2] = [
+    BrailleChar::with_data(0b01110001),
+    BrailleChar::with_data(0b10001110),
+];
+
+/// Frames of a loading spinner, meant to be shown one after another. `⣾⣽⣻⢿⡿⣟⣯⣷`
+pub static SPINNER: [BrailleChar; 8] = [
+    BrailleChar::with_data(0b11111110),
+    BrailleChar::with_data(0b11111101),
+    BrailleChar::with_data(0b11111011),
+    BrailleChar::with_data(0b10111111),
+    BrailleChar::with_data(0b01111111),
+    BrailleChar::with_data(0b11011111),
+    BrailleChar::with_data(0b11101111),
+    BrailleChar::with_data(0b11110111),
+];
+
+
+/// Represents one of the predefined icons.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Icon {
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    Heart,
+    Check,
+    Cross,
+    /// Frame of the loading spinner. Wraps around after the last frame,
+    /// so incrementing the frame every tick gives an endless animation.
+    Spinner(usize),
+}
+
+
+impl Icon {
+    /// Returns the characters of the icon from left to right.
+    pub fn glyphs(&self) -> &'static [BrailleChar] {
+        match self {
+            Icon::ArrowUp => &ARROW_UP,
+            Icon::ArrowDown => &ARROW_DOWN,
+            Icon::ArrowLeft => &ARROW_LEFT,
+            Icon::ArrowRight => &ARROW_RIGHT,
+            Icon::Heart => &HEART,
+            Icon::Check => &CHECK,
+            Icon::Cross => &CROSS,
+            Icon::Spinner(frame) => std::slice::from_ref(&SPINNER[frame % SPINNER.len()]),
+        }
+    }
+}
+
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn icon_glyphs() {
+        let string: String = Icon::Cross.glyphs().iter().map(char::from).collect();
+        assert_eq!(string, "⡱⢎");
+
+        assert_eq!(Icon::Spinner(1).glyphs(), Icon::Spinner(9).glyphs());
+        assert_eq!(Icon::Spinner(0).glyphs().len(), 1);
+    }
+}
\ No newline at end of file
@@ -3,9 +3,27 @@
 //! A Rust crate that aims to simplify working with braille characters.
 //! If you actually want to use braille art in a project i recommend the [rsille](https://crates.io/crates/rsille) crate, since it will most likely be better maintained and has a lot more features.
 
+pub mod animator;
+pub mod ascii_canvas;
 pub mod braille_char;
 pub mod canvas;
+pub mod canvas_view;
+pub mod double_buffer;
 pub mod error;
+pub mod icons;
+pub mod plot;
+pub mod prelude;
+pub mod terminal;
 
-pub use canvas::Canvas;
-pub use braille_char::BrailleChar;
\ No newline at end of file
+pub use animator::{frames_to_asciicast, Animator};
+pub use ascii_canvas::{AsciiCanvas, OutputMode};
+pub use canvas::{
+    Canvas, CanvasSnapshot, CaptionOverflow, CellState, Connectivity, DotMode, DotOrder, MorphOp,
+    Origin,
+};
+pub use canvas_view::CanvasView;
+pub use braille_char::BrailleChar;
+pub use double_buffer::DoubleBuffer;
+pub use error::{IndexError, Result};
+pub use icons::Icon;
+pub use terminal::terminal_supports_braille;
\ No newline at end of file
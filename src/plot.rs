@@ -0,0 +1,79 @@
+//! Scaling helpers shared by plots, mapping data values onto points of a canvas.
+
+
+/// Share of the value range added below the minimum and above the maximum by `autoscale()`.
+const AUTOSCALE_PADDING: f64 = 0.05;
+
+
+/// Returns the range `(min, max)` covering all `values`, padded by 5% of the range on both sides,
+/// so the extremes don't sit directly on the edge of the plot.
+/// Values that aren't finite are ignored.
+/// A constant series gets a range of 1.0 around its value
+/// and without any values the range 0.0 to 1.0 is padded.
+pub fn autoscale(values: &[f64]) -> (f64, f64) {
+    let (min, max) = values
+        .iter()
+        .filter(|v| v.is_finite())
+        .fold(None, |range: Option<(f64, f64)>, &v| match range {
+            None => Some((v, v)),
+            Some((min, max)) => Some((min.min(v), max.max(v))),
+        })
+        .unwrap_or((0.0, 1.0));
+
+    if min == max {
+        return (min - 0.5, max + 0.5);
+    }
+
+    let padding = (max - min) * AUTOSCALE_PADDING;
+    (min - padding, max + padding)
+}
+
+
+/// Maps `v` from the range `min` to `max` onto a coordinate from 0 to `out_range - 1`,
+/// for example the points of a canvas axis. `min` maps to 0 and `max` to `out_range - 1`.
+/// Values outside the range, including infinities, get clamped and NaN maps to 0.
+/// If `min` equals `max` every value maps to the middle. If `out_range` is 0 returns 0.
+///
+/// The result grows with `v`, so on a canvas with the top left origin large values end up
+/// at the bottom. Use `Origin::BottomLeft` to plot them upwards.
+pub fn map_value(v: f64, min: f64, max: f64, out_range: usize) -> usize {
+    let last = out_range.saturating_sub(1);
+
+    if min == max {
+        return last / 2;
+    }
+
+    let t = ((v - min) / (max - min)).clamp(0.0, 1.0);
+
+    if t.is_nan() {
+        0
+    } else {
+        (t * last as f64).round() as usize
+    }
+}
+
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn plot_autoscale() {
+        assert_eq!(autoscale(&[0.0, 10.0, 5.0]), (-0.5, 10.5));
+        assert_eq!(autoscale(&[3.0, 3.0]), (2.5, 3.5));
+        assert_eq!(autoscale(&[f64::NAN, 2.0, f64::INFINITY]), (1.5, 2.5));
+        assert_eq!(autoscale(&[]), (-0.05, 1.05));
+    }
+
+    #[test]
+    fn plot_map_value() {
+        assert_eq!(map_value(0.0, 0.0, 10.0, 11), 0);
+        assert_eq!(map_value(10.0, 0.0, 10.0, 11), 10);
+        assert_eq!(map_value(4.4, 0.0, 10.0, 11), 4);
+        assert_eq!(map_value(-5.0, 0.0, 10.0, 11), 0);
+        assert_eq!(map_value(f64::INFINITY, 0.0, 10.0, 11), 10);
+        assert_eq!(map_value(f64::NAN, 0.0, 10.0, 11), 0);
+        assert_eq!(map_value(7.0, 3.0, 3.0, 11), 5);
+        assert_eq!(map_value(7.0, 0.0, 10.0, 0), 0);
+    }
+}
\ No newline at end of file
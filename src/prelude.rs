@@ -0,0 +1,27 @@
+//! Re-exports the most used types, so drawing code only needs a single import:
+//!
+//! ```
+//! use braille_rs::prelude::*;
+//!
+//! fn draw(canvas: &mut Canvas) -> Result<()> {
+//!     canvas.set(0, 0)?;
+//!     canvas.set(1, 1)
+//! }
+//!
+//! fn parse(s: &str) -> Result<usize, std::num::ParseIntError> {
+//!     s.parse()
+//! }
+//!
+//! let mut canvas = Canvas::with_size(2, 4);
+//! draw(&mut canvas).unwrap();
+//! assert_eq!(canvas.to_string(), "⠑");
+//! assert_eq!(parse("4"), Ok(4));
+//! ```
+
+pub use crate::{
+    braille_char::BrailleChar,
+    canvas::{Canvas, CaptionOverflow, CellState, Connectivity, DotMode, DotOrder, MorphOp, Origin},
+    canvas_view::CanvasView,
+    error::{IndexError, Result},
+    icons::Icon,
+};
\ No newline at end of file
@@ -0,0 +1,52 @@
+use std::env;
+
+
+/// Returns whether braille output is likely to render in the current terminal.
+///
+/// This is only a heuristic based on environment variables, not a guarantee.
+/// The locale is taken from `LC_ALL`, `LC_CTYPE` or `LANG`, whichever is set first,
+/// and has to use UTF-8. Terminals known to lack braille glyphs, like `TERM=dumb` or
+/// the Linux console, are ruled out. Combine with `OutputMode::detect()` to pick
+/// the ASCII fallback automatically.
+pub fn terminal_supports_braille() -> bool {
+    let var = |name: &str| env::var(name).ok();
+
+    supports_braille([var("LC_ALL"), var("LC_CTYPE"), var("LANG")], var("TERM"))
+}
+
+
+/// Decides `terminal_supports_braille()` from the locale variables in order of precedence
+/// and the terminal type.
+fn supports_braille(locale: [Option<String>; 3], term: Option<String>) -> bool {
+    if matches!(term.as_deref(), Some("dumb" | "linux")) {
+        return false;
+    }
+
+    locale
+        .into_iter()
+        .flatten()
+        .find(|value| !value.is_empty())
+        .is_some_and(|value| {
+            let value = value.to_ascii_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+}
+
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn terminal_supports_braille_heuristic() {
+        let some = |s: &str| Some(s.to_string());
+
+        assert!(supports_braille([None, None, some("en_US.UTF-8")], some("xterm-256color")));
+        assert!(supports_braille([None, some("C.utf8"), some("C")], None));
+        assert!(supports_braille([some(""), None, some("de_DE.UTF-8")], some("xterm")));
+        assert!(!supports_braille([some("C"), None, some("en_US.UTF-8")], some("xterm")));
+        assert!(!supports_braille([None, None, some("en_US.UTF-8")], some("linux")));
+        assert!(!supports_braille([None, None, some("en_US.UTF-8")], some("dumb")));
+        assert!(!supports_braille([None, None, None], some("xterm")));
+    }
+}
\ No newline at end of file